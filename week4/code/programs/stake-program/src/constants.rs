@@ -4,4 +4,33 @@ use anchor_lang::constant;
 pub const REWARD_VAULT_SEED: &[u8] = b"reward";
 
 #[constant]
-pub const STAKE_INFO_SEED: &[u8] = b"stake_info";
\ No newline at end of file
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+
+#[constant]
+pub const STAKE_INFO_SEED: &[u8] = b"stake_info";
+
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+#[constant]
+pub const UNBONDING_SEED: &[u8] = b"unbonding";
+
+#[constant]
+pub const POSITION_MINT_SEED: &[u8] = b"position";
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Hệ số phóng đại của chỉ số phần thưởng trong `StakeConfig`, để lãi suất nhỏ
+/// trên kỳ dài không bị làm tròn về 0 khi chốt chỉ số mỗi giây.
+pub const REWARD_INDEX_PRECISION: u128 = 1_000_000_000_000;
+
+/// Số giây trong một năm (365 ngày), dùng để giới hạn APR.
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Hệ số của vị thế không khóa hoặc đã hết hạn khóa.
+pub const BASE_MULTIPLIER_BPS: u64 = 10_000;
+
+/// Các mức khóa (số ngày, hệ số nhân phần thưởng tính bằng basis point).
+pub const LOCK_TIERS: [(u16, u64); 4] = [(0, BASE_MULTIPLIER_BPS), (30, 12_500), (90, 15_000), (180, 20_000)];
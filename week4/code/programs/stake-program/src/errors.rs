@@ -10,4 +10,30 @@ pub enum AppError {
     NoToken,
     #[msg("Invalid mint")]
     InvalidMint,
+    #[msg("Only the admin can do this")]
+    Unauthorized,
+    #[msg("Reward period length must be greater than zero")]
+    InvalidPeriod,
+    #[msg("Lock duration is not one of the supported tiers")]
+    InvalidLockTier,
+    #[msg("Tokens are still locked")]
+    StillLocked,
+    #[msg("Penalty must be at most 10000 basis points and needs the stake and reward mints to match")]
+    InvalidPenalty,
+    #[msg("The pool is paused")]
+    Paused,
+    #[msg("Unbonding period has not ended yet")]
+    StillUnbonding,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("This position already has an NFT")]
+    PositionNftExists,
+    #[msg("Only a different position without unbonding tokens can be merged")]
+    CannotMerge,
+    #[msg("Emergency withdraw is only available while the pool is paused")]
+    NotPaused,
+    #[msg("The position still holds tokens or unpaid rewards")]
+    PositionNotEmpty,
+    #[msg("This pool has an unbonding period; use request_unstake instead")]
+    UnbondingRequired,
 }
@@ -0,0 +1,110 @@
+use crate::constants::{CONFIG_SEED, REWARD_VAULT_SEED, STAKE_INFO_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+        constraint = !config.paused @ AppError::Paused,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = reward_mint,
+        associated_token::authority = staker,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub staker_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn claim(ctx: Context<Claim>) -> Result<()> {
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+
+    let clock = Clock::get()?;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    // Đã rút hết token gốc vẫn được claim phần thưởng còn nợ
+    if stake_info.is_empty() {
+        return Err(AppError::NotStaked.into());
+    }
+
+    // Phần thưởng tính trên toàn bộ số token đang stake
+    stake_info.accrue(&mut ctx.accounts.config, clock.unix_timestamp)?;
+    let reward = stake_info.take_reward(&mut ctx.accounts.config, ctx.accounts.reward_vault.amount)?;
+
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let reward_vault_bump = ctx.bumps.reward_vault;
+    let reward_seeds: &[&[u8]] = &[
+        REWARD_VAULT_SEED,
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        &[reward_vault_bump],
+    ];
+    let reward_signer: &[&[&[u8]]] = &[reward_seeds];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.reward_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.staker_reward_account.to_account_info(),
+                authority: ctx.accounts.reward_vault.to_account_info(),
+            },
+            reward_signer,
+        ),
+        reward,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::constants::STAKE_INFO_SEED;
+use crate::errors::AppError;
+use crate::state::StakeInfo;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{burn, close_account, Burn, CloseAccount, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+        has_one = position_mint,
+        constraint = stake_info.is_empty() @ AppError::PositionNotEmpty,
+        close = holder,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub position_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = holder,
+        token::token_program = token_program,
+    )]
+    pub position_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Đóng một vị thế có NFT đã rút hết: đốt NFT, đóng token account và mint của
+/// nó rồi trả toàn bộ rent (kể cả của `stake_info`) cho người giữ NFT.
+pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    let holder = ctx.accounts.holder.to_account_info();
+    ctx.accounts
+        .stake_info
+        .authorize(holder.key(), Some(&ctx.accounts.position_token_account))?;
+
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let mint = ctx.accounts.position_mint.to_account_info();
+    let position_token = ctx.accounts.position_token_account.to_account_info();
+
+    burn(
+        CpiContext::new(
+            token_program.clone(),
+            Burn {
+                mint: mint.clone(),
+                from: position_token.clone(),
+                authority: holder.clone(),
+            },
+        ),
+        1,
+    )?;
+    close_account(CpiContext::new(
+        token_program.clone(),
+        CloseAccount {
+            account: position_token,
+            destination: holder.clone(),
+            authority: holder.clone(),
+        },
+    ))?;
+
+    // stake_info là close authority của mint (xem `create_position_nft`)
+    let staker_key = ctx.accounts.stake_info.staker;
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let position_index = ctx.accounts.stake_info.position_index.to_le_bytes();
+    let stake_info_bump = ctx.bumps.stake_info;
+    let stake_seeds: &[&[u8]] = &[
+        STAKE_INFO_SEED,
+        staker_key.as_ref(),
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        position_index.as_ref(),
+        &[stake_info_bump],
+    ];
+    close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: mint,
+            destination: holder,
+            authority: ctx.accounts.stake_info.to_account_info(),
+        },
+        &[stake_seeds],
+    ))?;
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use crate::constants::{CONFIG_SEED, POSITION_MINT_SEED, STAKE_INFO_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_2022::{initialize_mint2, mint_to, set_authority, InitializeMint2, MintTo, SetAuthority, Token2022};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    metadata_pointer_initialize, mint_close_authority_initialize, non_transferable_mint_initialize,
+    token_metadata_initialize, token_metadata_update_field, Mint, MetadataPointerInitialize,
+    MintCloseAuthorityInitialize, NonTransferableMintInitialize, TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
+
+const POSITION_NAME: &str = "Stake Position";
+const POSITION_SYMBOL: &str = "STAKE";
+
+#[derive(Accounts)]
+pub struct CreatePositionNft<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            staker.key().as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = staker,
+        has_one = stake_mint,
+        has_one = reward_mint,
+        constraint = !stake_info.has_position_nft() @ AppError::PositionNftExists,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// CHECK: Mint Token-2022 được tạo trong instruction này tại PDA của vị thế.
+    #[account(
+        mut,
+        seeds = [POSITION_MINT_SEED, stake_info.key().as_ref()],
+        bump,
+    )]
+    pub position_mint: UncheckedAccount<'info>,
+
+    /// CHECK: ATA (Token-2022) của staker cho `position_mint`, được tạo qua associated token program.
+    #[account(mut)]
+    pub position_token_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Phát hành NFT (Token-2022, supply 1) đại diện cho vị thế. Metadata của NFT
+/// ghi địa chỉ `StakeInfo` để ví và marketplace hiển thị được vị thế.
+pub fn create_position_nft(ctx: Context<CreatePositionNft>) -> Result<()> {
+    let stake_info_key = ctx.accounts.stake_info.key();
+    let mint_key = ctx.accounts.position_mint.key();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let mint = ctx.accounts.position_mint.to_account_info();
+    let stake_info = ctx.accounts.stake_info.to_account_info();
+
+    let mut extensions = vec![ExtensionType::MetadataPointer, ExtensionType::MintCloseAuthority];
+    if !ctx.accounts.config.transferable_positions {
+        extensions.push(ExtensionType::NonTransferable);
+    }
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)?;
+
+    let mut metadata = TokenMetadata {
+        mint: mint_key,
+        name: POSITION_NAME.to_string(),
+        symbol: POSITION_SYMBOL.to_string(),
+        ..TokenMetadata::default()
+    };
+    metadata.update(Field::Key("stake_info".to_string()), stake_info_key.to_string());
+    // Metadata được ghi sau khi khởi tạo mint nên chỉ cần nạp đủ rent trước
+    let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+
+    let mint_bump = ctx.bumps.position_mint;
+    let mint_seeds: &[&[u8]] = &[POSITION_MINT_SEED, stake_info_key.as_ref(), &[mint_bump]];
+    create_pda_account(
+        &ctx.accounts.staker.to_account_info(),
+        &mint,
+        &ctx.accounts.system_program.to_account_info(),
+        lamports,
+        space as u64,
+        token_program.key,
+        mint_seeds,
+    )?;
+
+    if !ctx.accounts.config.transferable_positions {
+        non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ))?;
+    }
+    metadata_pointer_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        Some(stake_info_key),
+        Some(mint_key),
+    )?;
+    // stake_info đóng được mint (hoàn rent) khi `close_position` đốt NFT
+    mint_close_authority_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            MintCloseAuthorityInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        Some(&stake_info_key),
+    )?;
+    initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: mint.clone() }),
+        0,
+        &stake_info_key,
+        None,
+    )?;
+
+    // stake_info là mint authority và update authority của metadata
+    let staker_key = ctx.accounts.staker.key();
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let position_index = ctx.accounts.stake_info.position_index.to_le_bytes();
+    let stake_info_bump = ctx.bumps.stake_info;
+    let stake_seeds: &[&[u8]] = &[
+        STAKE_INFO_SEED,
+        staker_key.as_ref(),
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        position_index.as_ref(),
+        &[stake_info_bump],
+    ];
+    let stake_signer: &[&[&[u8]]] = &[stake_seeds];
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: token_program.clone(),
+                metadata: mint.clone(),
+                update_authority: stake_info.clone(),
+                mint_authority: stake_info.clone(),
+                mint: mint.clone(),
+            },
+            stake_signer,
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+    token_metadata_update_field(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataUpdateField {
+                program_id: token_program.clone(),
+                metadata: mint.clone(),
+                update_authority: stake_info.clone(),
+            },
+            stake_signer,
+        ),
+        Field::Key("stake_info".to_string()),
+        stake_info_key.to_string(),
+    )?;
+
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.staker.to_account_info(),
+            associated_token: ctx.accounts.position_token_account.to_account_info(),
+            authority: ctx.accounts.staker.to_account_info(),
+            mint: mint.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: token_program.clone(),
+        },
+    ))?;
+
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: mint.clone(),
+                to: ctx.accounts.position_token_account.to_account_info(),
+                authority: stake_info.clone(),
+            },
+            stake_signer,
+        ),
+        1,
+    )?;
+
+    // Bỏ mint authority để supply cố định là 1
+    set_authority(
+        CpiContext::new_with_signer(
+            token_program,
+            SetAuthority {
+                current_authority: stake_info,
+                account_or_mint: mint,
+            },
+            stake_signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    ctx.accounts.stake_info.position_mint = mint_key;
+
+    Ok(())
+}
+
+/// Tạo account tại PDA như `init` của Anchor. `create_account` thất bại khi địa
+/// chỉ đã có lamports, mà ai cũng chuyển SOL vào PDA trước được để chặn vị thế
+/// phát hành NFT; khi đó chỉ nạp thêm cho đủ rent rồi allocate và assign.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let current = account.lamports();
+    if current == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[seeds],
+            ),
+            lamports,
+            space,
+            owner,
+        );
+    }
+
+    let top_up = lamports.saturating_sub(current);
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[seeds],
+        ),
+        owner,
+    )
+}
@@ -0,0 +1,110 @@
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED, STAKE_VAULT_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staker,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Chỉ dùng khi admin đã tạm dừng pool: trả lại token gốc kể cả khi còn trong
+/// thời gian khóa, không phạt. Toàn bộ phần thưởng (đã tích lũy hay còn nợ) bị bỏ.
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    if !stake_info.is_staked || stake_info.amount == 0 {
+        return Err(AppError::NotStaked.into());
+    }
+
+    let amount = stake_info.forfeit(&mut ctx.accounts.config)?;
+
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let stake_vault_bump = ctx.bumps.stake_vault;
+    let vault_seeds: &[&[u8]] = &[
+        STAKE_VAULT_SEED,
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        &[stake_vault_bump],
+    ];
+    let vault_signer: &[&[&[u8]]] = &[vault_seeds];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.stake_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.staker_token_account.to_account_info(),
+                authority: ctx.accounts.stake_vault.to_account_info(),
+            },
+            vault_signer,
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    // Còn token đang unbonding (rút qua `withdraw_unbonded`) thì chưa đóng stake_info
+    if stake_info.can_close() {
+        stake_info.close(ctx.accounts.staker.to_account_info())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::constants::{CONFIG_SEED, REWARD_VAULT_SEED};
+use crate::errors::AppError;
+use crate::state::StakeConfig;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub admin: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+        has_one = admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = admin,
+        token::token_program = reward_token_program,
+    )]
+    pub admin_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Err(AppError::NoToken.into());
+    }
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.reward_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.admin_reward_account.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    Ok(())
+}
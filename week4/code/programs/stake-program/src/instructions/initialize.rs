@@ -1,6 +1,9 @@
-use crate::constants::REWARD_VAULT_SEED;
+use crate::constants::{CONFIG_SEED, REWARD_VAULT_SEED, STAKE_VAULT_SEED};
+use crate::errors::AppError;
+use crate::program::StakeProgram;
+use crate::state::StakeConfig;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction()]
@@ -8,22 +11,81 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    // Chỉ upgrade authority của chương trình mới được khởi tạo pool
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, StakeProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ AppError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        space = 8 + StakeConfig::INIT_SPACE
+    )]
+    pub config: Account<'info, StakeConfig>,
 
     #[account(
-        init_if_needed,
+        init,
         payer = admin,
-        seeds = [REWARD_VAULT_SEED, mint.key().as_ref()],
+        seeds = [REWARD_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
         bump,
-        token::mint = mint,
+        token::mint = reward_mint,
         token::authority = reward_vault,
+        token::token_program = reward_token_program,
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Một vault chung giữ token gốc của mọi vị thế trong pool
+    #[account(
+        init,
+        payer = admin,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    // Mỗi mint có thể thuộc SPL Token hoặc Token-2022, độc lập với nhau
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn initialize(_ctx: Context<Initialize>) -> Result<()> {
+pub fn initialize(ctx: Context<Initialize>,
+                  reward_rate_bps: u64,
+                  period_length: u64,
+                  max_apr_bps: u64,
+                  early_unstake_penalty_bps: u64,
+                  unbonding_period: u64,
+                  transferable_positions: bool) -> Result<()> {
+    let config = StakeConfig {
+        admin: ctx.accounts.admin.key(),
+        stake_mint: ctx.accounts.stake_mint.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        reward_rate_bps,
+        period_length,
+        max_apr_bps,
+        early_unstake_penalty_bps,
+        unbonding_period,
+        transferable_positions,
+        total_liabilities: 0,
+        paused: false,
+        bump: ctx.bumps.config,
+        reward_index: 0,
+        apr_index: 0,
+        index_updated_at: Clock::get()?.unix_timestamp,
+    };
+    config.validate()?;
+    ctx.accounts.config.set_inner(config);
+
     Ok(())
 }
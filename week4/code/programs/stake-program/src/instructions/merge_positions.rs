@@ -0,0 +1,82 @@
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    // Vị thế bị gộp; không được còn token đang unbonding. Nếu có NFT thì được giữ
+    // lại (đã rỗng) để người giữ NFT đóng bằng `close_position`
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            source.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            source.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+        constraint = source.unbonding_amount == 0 @ AppError::CannotMerge,
+    )]
+    pub source: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            destination.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            destination.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+        constraint = destination.key() != source.key() @ AppError::CannotMerge,
+    )]
+    pub destination: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT của vị thế đích; chỉ cần khi vị thế đó đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account giữ NFT của vị thế nguồn; chỉ cần khi vị thế đó đã có NFT.
+    pub source_position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Gộp vị thế `source` vào `destination` rồi đóng `source` nếu nó không có NFT.
+/// Token gốc đã nằm trong vault chung nên không cần chuyển token.
+pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+    let source_token = ctx.accounts.source_position_token_account.as_deref();
+    ctx.accounts.source.authorize(ctx.accounts.staker.key(), source_token)?;
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.destination.authorize(ctx.accounts.staker.key(), position_token)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .destination
+        .merge(&mut ctx.accounts.config, &mut ctx.accounts.source, now)?;
+
+    if ctx.accounts.source.can_close() {
+        ctx.accounts.source.close(ctx.accounts.staker.to_account_info())?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,31 @@
+pub mod claim;
+pub mod close_position;
+pub mod create_position_nft;
+pub mod emergency_withdraw;
+pub mod fund_rewards;
 pub mod initialize;
+pub mod merge_positions;
+pub mod request_unstake;
+pub mod set_paused;
+pub mod simulate_reward;
 pub mod stake;
+pub mod top_up;
 pub mod unstake;
+pub mod update_config;
+pub mod withdraw_unbonded;
 
+pub use claim::*;
+pub use close_position::*;
+pub use create_position_nft::*;
+pub use emergency_withdraw::*;
+pub use fund_rewards::*;
 pub use initialize::*;
+pub use merge_positions::*;
+pub use request_unstake::*;
+pub use set_paused::*;
+pub use simulate_reward::*;
 pub use stake::*;
-pub use unstake::*;
\ No newline at end of file
+pub use top_up::*;
+pub use unstake::*;
+pub use update_config::*;
+pub use withdraw_unbonded::*;
@@ -0,0 +1,91 @@
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED, UNBONDING_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo, UnbondingTicket};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = staker,
+        seeds = [UNBONDING_SEED, stake_info.key().as_ref(), stake_info.next_ticket_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + UnbondingTicket::INIT_SPACE
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    if !stake_info.is_staked || stake_info.amount == 0 {
+        return Err(AppError::NotStaked.into());
+    }
+
+    if amount == 0 || amount > stake_info.amount {
+        return Err(AppError::NoToken.into());
+    }
+
+    // Rút trước hạn (có phạt) chỉ đi qua `unstake`
+    if stake_info.is_locked(now) {
+        return Err(AppError::StillLocked.into());
+    }
+
+    // Phần thưởng tích lũy đến lúc này vẫn giữ lại để claim
+    let config = &mut ctx.accounts.config;
+    let id = stake_info.start_unbonding(config, amount, now)?;
+
+    let release_at = now
+        .checked_add(config.unbonding_period as i64)
+        .ok_or(AppError::MathOverflow)?;
+
+    ctx.accounts.ticket.set_inner(UnbondingTicket {
+        staker: ctx.accounts.staker.key(),
+        stake_info: stake_info.key(),
+        id,
+        amount,
+        release_at,
+        bump: ctx.bumps.ticket,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::constants::CONFIG_SEED;
+use crate::errors::AppError;
+use crate::state::StakeConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, config.stake_mint.as_ref(), config.reward_mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, StakeConfig>,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SimulateReward<'info> {
+    #[account(
+        seeds = [CONFIG_SEED, config.stake_mint.as_ref(), config.reward_mint.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            config.stake_mint.as_ref(),
+            config.reward_mint.as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+/// Phần thưởng staker sẽ nhận nếu claim ngay bây giờ (đã gồm phần còn nợ),
+/// không thay đổi trạng thái nào.
+pub fn simulate_reward(ctx: Context<SimulateReward>) -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    let stake_info = &ctx.accounts.stake_info;
+
+    let unaccrued = stake_info.unaccrued_reward(&ctx.accounts.config, now)?;
+
+    Ok(stake_info.pending_reward.checked_add(unaccrued).ok_or(AppError::MathOverflow)?)
+}
@@ -1,74 +1,134 @@
-use crate::constants::STAKE_INFO_SEED;
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED, STAKE_VAULT_SEED};
 use crate::errors::AppError;
-use crate::state::StakeInfo;
+use crate::state::{StakeConfig, StakeInfo};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
-#[instruction()]
+#[instruction(amount: u64, lock_days: u16, position_index: u64)]
 pub struct Stake<'info> {
     #[account(mut)]
     pub staker: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+        constraint = !config.paused @ AppError::Paused,
+    )]
+    pub config: Account<'info, StakeConfig>,
 
     #[account(
         init_if_needed,
         payer = staker,
-        seeds = [STAKE_INFO_SEED, staker.key().as_ref(), mint.key().as_ref()],
+        seeds = [
+            STAKE_INFO_SEED,
+            staker.key().as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            position_index.to_le_bytes().as_ref(),
+        ],
         bump,
         space = 8 + StakeInfo::INIT_SPACE
     )]
     pub stake_info: Account<'info, StakeInfo>,
 
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
-        init_if_needed,
-        payer = staker,
-        associated_token::mint = mint,
-        associated_token::authority = stake_info,
+        mut,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
+        associated_token::mint = stake_mint,
         associated_token::authority = staker,
+        associated_token::token_program = stake_token_program,
     )]
-    pub staker_token_account: Account<'info, TokenAccount>,
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+/// Mỗi staker có thể mở nhiều vị thế độc lập cho cùng một pool, phân biệt bằng
+/// `position_index`; nạp vào index đã có thì cộng dồn vào vị thế đó.
+pub fn stake(ctx: Context<Stake>, amount: u64, lock_days: u16, position_index: u64) -> Result<()> {
     if amount == 0 {
         return Err(AppError::NoToken.into());
     }
 
+    // Vị thế đã có NFT: người ký phải vừa là `staker` vừa giữ NFT. Người giữ NFT
+    // khác (địa chỉ vị thế không suy ra được từ khóa của họ) nạp thêm qua `top_up`.
+    if ctx.accounts.stake_info.has_position_nft() {
+        let position_token = ctx.accounts.position_token_account.as_deref();
+        ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+    }
+
+    let received = deposit_to_vault(
+        &ctx.accounts.stake_token_program,
+        &ctx.accounts.staker_token_account,
+        &ctx.accounts.stake_mint,
+        &mut ctx.accounts.stake_vault,
+        &ctx.accounts.staker,
+        amount,
+    )?;
+
     let clock = Clock::get()?;
     let stake_info = &mut ctx.accounts.stake_info;
 
     stake_info.staker = ctx.accounts.staker.key();
-    stake_info.mint = ctx.accounts.mint.key();
-    stake_info.stake_at = clock.slot;
-    stake_info.is_staked = true;
-    stake_info.amount = stake_info.amount.checked_add(amount).unwrap();
+    stake_info.position_index = position_index;
+    stake_info.stake_mint = ctx.accounts.stake_mint.key();
+    stake_info.reward_mint = ctx.accounts.reward_mint.key();
+    // Tích lũy phần thưởng cũ trước khi nạp thêm
+    stake_info.deposit(&mut ctx.accounts.config, received, clock.unix_timestamp)?;
+    stake_info.lock(lock_days, clock.unix_timestamp)?;
 
-    transfer(
+    Ok(())
+}
+
+/// Chuyển `amount` stake token từ `from` vào stake vault và trả về số vault thực nhận:
+/// mint Token-2022 có thể thu phí chuyển khoản nên chỉ ghi nhận phần đó.
+pub(crate) fn deposit_to_vault<'info>(token_program: &Interface<'info, TokenInterface>,
+                                      from: &InterfaceAccount<'info, TokenAccount>,
+                                      stake_mint: &InterfaceAccount<'info, Mint>,
+                                      stake_vault: &mut InterfaceAccount<'info, TokenAccount>,
+                                      authority: &Signer<'info>,
+                                      amount: u64) -> Result<u64> {
+    let vault_before = stake_vault.amount;
+    transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.staker_token_account.to_account_info(),
-                to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: ctx.accounts.staker.to_account_info(),
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: stake_mint.to_account_info(),
+                to: stake_vault.to_account_info(),
+                authority: authority.to_account_info(),
             },
         ),
         amount,
+        stake_mint.decimals,
     )?;
-
-    Ok(())
+    stake_vault.reload()?;
+    let received = stake_vault.amount.checked_sub(vault_before).ok_or(AppError::MathOverflow)?;
+    if received == 0 {
+        return Err(AppError::NoToken.into());
+    }
+    Ok(received)
 }
@@ -0,0 +1,89 @@
+use crate::constants::{CONFIG_SEED, STAKE_INFO_SEED, STAKE_VAULT_SEED};
+use crate::errors::AppError;
+use crate::instructions::stake::deposit_to_vault;
+use crate::state::{StakeConfig, StakeInfo};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct TopUp<'info> {
+    pub holder: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+        constraint = !config.paused @ AppError::Paused,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    // Vị thế đã có, tìm theo `staker` ban đầu thay vì theo người ký
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = holder,
+        token::token_program = stake_token_program,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Nạp thêm vào một vị thế đã có. Khác `stake`, địa chỉ vị thế không suy ra từ
+/// người ký nên người giữ NFT vị thế cũng dùng được.
+pub fn top_up(ctx: Context<TopUp>, amount: u64, lock_days: u16) -> Result<()> {
+    if amount == 0 {
+        return Err(AppError::NoToken.into());
+    }
+
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.holder.key(), position_token)?;
+
+    let received = deposit_to_vault(
+        &ctx.accounts.stake_token_program,
+        &ctx.accounts.holder_token_account,
+        &ctx.accounts.stake_mint,
+        &mut ctx.accounts.stake_vault,
+        &ctx.accounts.holder,
+        amount,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let stake_info = &mut ctx.accounts.stake_info;
+    stake_info.deposit(&mut ctx.accounts.config, received, now)?;
+    stake_info.lock(lock_days, now)?;
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
-use crate::constants::{REWARD_VAULT_SEED, STAKE_INFO_SEED};
+use crate::constants::{CONFIG_SEED, REWARD_VAULT_SEED, STAKE_INFO_SEED, STAKE_VAULT_SEED};
 use crate::errors::AppError;
-use crate::state::StakeInfo;
+use crate::state::{StakeConfig, StakeInfo};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
 
 #[derive(Accounts)]
@@ -10,47 +10,82 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub staker: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
 
     #[account(
         mut,
-        seeds = [STAKE_INFO_SEED, staker.key().as_ref(), mint.key().as_ref()],
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
         bump,
-        has_one = staker,
-        has_one = mint,
-        close = staker,
+        has_one = stake_mint,
+        has_one = reward_mint,
     )]
     pub stake_info: Account<'info, StakeInfo>,
 
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         mut,
-        token::mint = mint,
-        token::authority = stake_info,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [REWARD_VAULT_SEED, mint.key().as_ref()],
+        seeds = [REWARD_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
         bump,
-        token::mint = mint,
+        token::mint = reward_mint,
         token::authority = reward_vault,
+        token::token_program = reward_token_program,
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staker,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = reward_mint,
         associated_token::authority = staker,
+        associated_token::token_program = reward_token_program,
     )]
-    pub staker_token_account: Account<'info, TokenAccount>,
+    pub staker_reward_account: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn unstake(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+
     let clock = Clock::get()?;
     let stake_info = &mut ctx.accounts.stake_info;
 
@@ -62,67 +97,102 @@ pub fn unstake(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
         return Err(AppError::NoToken.into());
     }
 
-    // Tính phần thưởng: 1% mỗi block
-    let blocks = clock.slot - stake_info.stake_at;
-    let reward = unstake_amount
-        .checked_mul(blocks)
-        .unwrap()
-        .checked_div(100)
-        .unwrap();
+    // Pool có thời gian chờ thì chỉ rút qua `request_unstake`
+    ctx.accounts.config.check_instant_unstake()?;
+
+    // Rút trước hạn khóa: bị từ chối, hoặc chịu phạt nếu pool cho phép
+    let penalty = if stake_info.is_locked(clock.unix_timestamp) {
+        if ctx.accounts.config.early_unstake_penalty_bps == 0 {
+            return Err(AppError::StillLocked.into());
+        }
+        ctx.accounts.config.early_unstake_penalty(unstake_amount)
+    } else {
+        0
+    };
+
+    // Trả phần thưởng đã tích lũy trong giới hạn số dư reward vault; phần thiếu
+    // được ghi nợ để claim sau, không bao giờ giữ lại token gốc.
+    // Khi pool tạm dừng thì không trả thưởng, toàn bộ được ghi nợ.
+    stake_info.withdraw(&mut ctx.accounts.config, unstake_amount, clock.unix_timestamp)?;
+    let available = if ctx.accounts.config.paused { 0 } else { ctx.accounts.reward_vault.amount };
+    let reward = stake_info.take_reward(&mut ctx.accounts.config, available)?;
 
     // Tạo seeds cho reward signer
-    let mint_key = ctx.accounts.mint.key();
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
     let reward_vault_bump = ctx.bumps.reward_vault;
     let reward_seeds: &[&[u8]] = &[
         REWARD_VAULT_SEED,
-        mint_key.as_ref(),
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
         &[reward_vault_bump],
     ];
     let reward_signer: &[&[&[u8]]] = &[reward_seeds];
 
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reward_vault.to_account_info(),
-                to: ctx.accounts.staker_token_account.to_account_info(),
-                authority: ctx.accounts.reward_vault.to_account_info(),
-            },
-            reward_signer,
-        ),
-        reward,
-    )?;
+    if reward > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.reward_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.staker_reward_account.to_account_info(),
+                    authority: ctx.accounts.reward_vault.to_account_info(),
+                },
+                reward_signer,
+            ),
+            reward,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
 
-    // Tạo seeds cho stake signer
-    let staker_key = ctx.accounts.staker.key();
-    let stake_info_bump = ctx.bumps.stake_info;
-    let stake_seeds: &[&[u8]] = &[
-        STAKE_INFO_SEED,
-        staker_key.as_ref(),
-        mint_key.as_ref(),
-        &[stake_info_bump],
+    // Tạo seeds cho vault signer
+    let stake_vault_bump = ctx.bumps.stake_vault;
+    let vault_seeds: &[&[u8]] = &[
+        STAKE_VAULT_SEED,
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        &[stake_vault_bump],
     ];
-    let stake_signer: &[&[&[u8]]] = &[stake_seeds];
+    let vault_signer: &[&[&[u8]]] = &[vault_seeds];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_token_account.to_account_info(),
+            ctx.accounts.stake_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
                 to: ctx.accounts.staker_token_account.to_account_info(),
-                authority: stake_info.to_account_info(), 
+                authority: ctx.accounts.stake_vault.to_account_info(),
             },
-            stake_signer,
+            vault_signer,
         ),
-        unstake_amount,
+        unstake_amount - penalty,
+        ctx.accounts.stake_mint.decimals,
     )?;
 
-    // Cập nhật trạng thái
-    stake_info.amount -= unstake_amount;
-    if stake_info.amount == 0 {
-        stake_info.is_staked = false;
-    } else {
-        stake_info.stake_at = clock.slot;
+    // Tiền phạt được chia lại cho các staker khác qua reward vault; phạt chỉ bật
+    // khi hai mint trùng nhau (xem `validate`) nên dùng chung stake token program
+    if penalty > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.stake_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    mint: ctx.accounts.stake_mint.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.stake_vault.to_account_info(),
+                },
+                vault_signer,
+            ),
+            penalty,
+            ctx.accounts.stake_mint.decimals,
+        )?;
+    }
+
+    // Rút hết token gốc và không còn phần thưởng nợ thì đóng stake_info
+    if stake_info.can_close() {
+        stake_info.close(ctx.accounts.staker.to_account_info())?;
     }
 
     Ok(())
@@ -0,0 +1,36 @@
+use crate::constants::CONFIG_SEED;
+use crate::errors::AppError;
+use crate::state::StakeConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, config.stake_mint.as_ref(), config.reward_mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ AppError::Unauthorized,
+    )]
+    pub config: Account<'info, StakeConfig>,
+}
+
+pub fn update_config(ctx: Context<UpdateConfig>,
+                     reward_rate_bps: u64,
+                     period_length: u64,
+                     max_apr_bps: u64,
+                     early_unstake_penalty_bps: u64,
+                     unbonding_period: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    // Chốt phần thưởng đến hiện tại theo lãi suất cũ, lãi suất mới chỉ áp dụng từ đây
+    config.checkpoint(Clock::get()?.unix_timestamp)?;
+    config.reward_rate_bps = reward_rate_bps;
+    config.period_length = period_length;
+    config.max_apr_bps = max_apr_bps;
+    config.early_unstake_penalty_bps = early_unstake_penalty_bps;
+    config.unbonding_period = unbonding_period;
+    config.validate()?;
+
+    Ok(())
+}
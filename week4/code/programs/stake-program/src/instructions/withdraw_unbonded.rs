@@ -0,0 +1,114 @@
+use crate::constants::{STAKE_INFO_SEED, STAKE_VAULT_SEED, UNBONDING_SEED};
+use crate::errors::AppError;
+use crate::state::{StakeInfo, UnbondingTicket};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct WithdrawUnbonded<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            STAKE_INFO_SEED,
+            stake_info.staker.as_ref(),
+            stake_mint.key().as_ref(),
+            reward_mint.key().as_ref(),
+            stake_info.position_index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = stake_mint,
+        has_one = reward_mint,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Token account giữ NFT vị thế của người ký; chỉ cần khi vị thế đã có NFT.
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [UNBONDING_SEED, stake_info.key().as_ref(), ticket.id.to_le_bytes().as_ref()],
+        bump = ticket.bump,
+        has_one = stake_info,
+        close = staker,
+    )]
+    pub ticket: Account<'info, UnbondingTicket>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED, stake_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_vault,
+        token::token_program = stake_token_program,
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staker,
+        associated_token::token_program = stake_token_program,
+    )]
+    pub staker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Trả token của một ticket đã hết thời gian unbonding. Không bị chặn khi pool
+/// tạm dừng vì chỉ trả lại gốc.
+pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+    let position_token = ctx.accounts.position_token_account.as_deref();
+    ctx.accounts.stake_info.authorize(ctx.accounts.staker.key(), position_token)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let ticket = &ctx.accounts.ticket;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    if !ticket.is_released(now) {
+        return Err(AppError::StillUnbonding.into());
+    }
+
+    stake_info.finish_unbonding(ticket.amount)?;
+
+    let stake_mint_key = ctx.accounts.stake_mint.key();
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let stake_vault_bump = ctx.bumps.stake_vault;
+    let vault_seeds: &[&[u8]] = &[
+        STAKE_VAULT_SEED,
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        &[stake_vault_bump],
+    ];
+    let vault_signer: &[&[&[u8]]] = &[vault_seeds];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.stake_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.staker_token_account.to_account_info(),
+                authority: ctx.accounts.stake_vault.to_account_info(),
+            },
+            vault_signer,
+        ),
+        ticket.amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    // Ticket cuối cùng và không còn thưởng nợ thì đóng stake_info
+    if stake_info.can_close() {
+        stake_info.close(ctx.accounts.staker.to_account_info())?;
+    }
+
+    Ok(())
+}
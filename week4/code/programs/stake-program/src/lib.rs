@@ -12,15 +12,106 @@ declare_id!("81WWst6eHgu6xi5UL63D5t37K2aXLwVNPqThhMFm4ury");
 pub mod stake_program {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize(ctx)
+    pub fn initialize(ctx: Context<Initialize>,
+                      reward_rate_bps: u64,
+                      period_length: u64,
+                      max_apr_bps: u64,
+                      early_unstake_penalty_bps: u64,
+                      unbonding_period: u64,
+                      transferable_positions: bool) -> Result<()> {
+        instructions::initialize(ctx,
+                                 reward_rate_bps,
+                                 period_length,
+                                 max_apr_bps,
+                                 early_unstake_penalty_bps,
+                                 unbonding_period,
+                                 transferable_positions)
     }
 
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        instructions::stake(ctx, amount)
+    pub fn update_config(ctx: Context<UpdateConfig>,
+                         reward_rate_bps: u64,
+                         period_length: u64,
+                         max_apr_bps: u64,
+                         early_unstake_penalty_bps: u64,
+                         unbonding_period: u64) -> Result<()> {
+        instructions::update_config(ctx,
+                                    reward_rate_bps,
+                                    period_length,
+                                    max_apr_bps,
+                                    early_unstake_penalty_bps,
+                                    unbonding_period)
     }
 
+    /// Tạm dừng hoặc mở lại pool (chỉ admin). Khi tạm dừng, `stake` và việc
+    /// trả thưởng bị chặn; staker vẫn rút được gốc.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    /// Nạp thêm token thưởng vào reward vault của pool (chỉ admin).
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        instructions::fund_rewards(ctx, amount)
+    }
+
+    /// `lock_days` là một trong các mức của `LOCK_TIERS`; `position_index` chọn
+    /// vị thế của staker (index mới thì mở vị thế mới).
+    pub fn stake(ctx: Context<Stake>, amount: u64, lock_days: u16, position_index: u64) -> Result<()> {
+        instructions::stake(ctx, amount, lock_days, position_index)
+    }
+
+    /// Nạp thêm vào vị thế đã có, tìm theo địa chỉ thay vì theo người ký; đường
+    /// nạp thêm của người giữ NFT vị thế.
+    pub fn top_up(ctx: Context<TopUp>, amount: u64, lock_days: u16) -> Result<()> {
+        instructions::top_up(ctx, amount, lock_days)
+    }
+
+    /// Gộp hai vị thế của cùng pool thành một. Vị thế nguồn bị đóng, trừ khi có
+    /// NFT: khi đó nó còn lại rỗng để đóng bằng `close_position`.
+    pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+        instructions::merge_positions(ctx)
+    }
+
+    /// Rút ngay, chỉ khi pool không có `unbonding_period`; ngược lại dùng
+    /// `request_unstake`.
     pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         instructions::unstake(ctx, amount)
     }
+
+    /// Bắt đầu rút hai bước: `amount` ngừng sinh thưởng và rút được bằng
+    /// `withdraw_unbonded` sau `unbonding_period` giây.
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake(ctx, amount)
+    }
+
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+        instructions::withdraw_unbonded(ctx)
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        instructions::claim(ctx)
+    }
+
+    /// Phát hành NFT đại diện cho vị thế. Từ đó người giữ NFT (thay cho
+    /// `staker`) mới được rút, claim, nạp thêm (`top_up`) và gộp; các instruction
+    /// đó nhận thêm `position_token_account` của người ký.
+    pub fn create_position_nft(ctx: Context<CreatePositionNft>) -> Result<()> {
+        instructions::create_position_nft(ctx)
+    }
+
+    /// Đốt NFT của một vị thế đã rút hết và hoàn rent cho người giữ NFT.
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    /// Chỉ đọc: trả về (qua return data) phần thưởng có thể claim ngay lúc này,
+    /// để ví hiển thị chính xác mà không cần gửi giao dịch.
+    pub fn simulate_reward(ctx: Context<SimulateReward>) -> Result<u64> {
+        instructions::simulate_reward(ctx)
+    }
+
+    /// Rút toàn bộ gốc và bỏ phần thưởng, bỏ qua thời gian khóa; chỉ dùng được
+    /// khi admin đã tạm dừng pool.
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        instructions::emergency_withdraw(ctx)
+    }
 }
@@ -1,15 +1,945 @@
+use crate::constants::{BASE_MULTIPLIER_BPS, BPS_DENOMINATOR, LOCK_TIERS, REWARD_INDEX_PRECISION, SECONDS_PER_DAY, SECONDS_PER_YEAR};
+use crate::errors::AppError;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
 
 #[account]
 #[derive(InitSpace)]
 pub struct StakeInfo {
     pub staker: Pubkey,
 
-    pub mint: Pubkey,
+    /// Số thứ tự vị thế của staker trong pool; mỗi index là một vị thế độc lập.
+    pub position_index: u64,
 
-    pub stake_at: u64,
+    pub stake_mint: Pubkey,
+
+    pub reward_mint: Pubkey,
+
+    /// Thời điểm (unix timestamp) phần thưởng được tích lũy lần cuối.
+    pub stake_at: i64,
 
     pub is_staked: bool,
 
     pub amount: u64,
+
+    /// Phần thưởng đã tích lũy đến `stake_at` nhưng chưa được trả.
+    pub pending_reward: u64,
+
+    /// Không được rút trước thời điểm này (unix timestamp), trừ khi chịu phạt.
+    pub lock_until: i64,
+
+    /// Hệ số nhân phần thưởng của mức khóa đã chọn, tính bằng basis point.
+    pub multiplier_bps: u64,
+
+    /// Tổng token đang chờ rút trong các `UnbondingTicket`, vẫn nằm trong stake vault
+    /// nhưng không còn sinh thưởng.
+    pub unbonding_amount: u64,
+
+    /// Id của ticket unbonding tiếp theo.
+    pub next_ticket_id: u64,
+
+    /// Mint của NFT đại diện cho vị thế, `Pubkey::default()` nếu chưa tạo.
+    /// Khi đã có NFT, người giữ NFT (không phải `staker`) mới được rút, claim,
+    /// nạp thêm, gộp và đóng vị thế. `staker` vẫn là một phần seeds của vị thế.
+    pub position_mint: Pubkey,
+
+    /// `StakeConfig::reward_index` tại lần tích lũy cuối.
+    pub reward_index_snapshot: u128,
+
+    /// `StakeConfig::apr_index` tại lần tích lũy cuối.
+    pub apr_index_snapshot: u128,
+}
+
+impl StakeInfo {
+    /// Phần thưởng từ `stake_at` đến `now` chưa được cộng vào `pending_reward`.
+    pub fn unaccrued_reward(&self, config: &StakeConfig, now: i64) -> Result<u64> {
+        let (reward_index, apr_index) = config.indices_at(now)?;
+        self.reward_until(reward_index, apr_index, now)
+    }
+
+    /// Phần thưởng từ `stake_at` đến `now`, khi hai chỉ số tại `now` là các giá trị
+    /// cho trước. Hệ số khóa chỉ áp dụng khi còn khóa: nếu khóa hết hạn giữa khoảng
+    /// này thì phần thưởng thêm của hệ số được chia theo thời gian còn khóa.
+    fn reward_until(&self, reward_index: u128, apr_index: u128, now: i64) -> Result<u64> {
+        let boosted = self.reward_between(reward_index, apr_index, self.multiplier_bps)?;
+        if self.lock_until >= now {
+            return Ok(boosted);
+        }
+        let base = self.reward_between(reward_index, apr_index, BASE_MULTIPLIER_BPS)?;
+        let window = now.saturating_sub(self.stake_at);
+        let locked = self.lock_until.saturating_sub(self.stake_at).clamp(0, window.max(0));
+        if window <= 0 || boosted <= base {
+            return Ok(boosted.min(base));
+        }
+        // locked < window nên phần thêm nhỏ hơn boosted - base, tổng không tràn
+        let bonus = (boosted - base) as u128 * locked as u128 / window as u128;
+        Ok(base + bonus as u64)
+    }
+
+    /// Phần thưởng của số token đang stake giữa snapshot và hai chỉ số cho trước.
+    /// Tính trên u128 và nhân hết (kể cả hệ số khóa) rồi mới chia một lần để không
+    /// mất độ chính xác; chỉ lỗi khi chính kết quả không vừa u64.
+    fn reward_between(&self, reward_index: u128, apr_index: u128, multiplier_bps: u64) -> Result<u64> {
+        let reward_delta = reward_index.saturating_sub(self.reward_index_snapshot);
+        let apr_delta = apr_index.saturating_sub(self.apr_index_snapshot);
+        let by_rate = match (self.amount as u128)
+            .checked_mul(multiplier_bps as u128)
+            .and_then(|value| value.checked_mul(reward_delta))
+        {
+            Some(value) => value / (BPS_DENOMINATOR as u128 * REWARD_INDEX_PRECISION),
+            // Mẫu số vừa u64 nên tử số tràn u128 nghĩa là thương lớn hơn u64::MAX
+            None => u128::MAX,
+        };
+        let by_apr = (self.amount as u128)
+            .checked_mul(apr_delta)
+            .map(|value| value / REWARD_INDEX_PRECISION)
+            .ok_or(AppError::MathOverflow)?;
+        Ok(u64::try_from(by_rate.min(by_apr)).map_err(|_| AppError::MathOverflow)?)
+    }
+
+    /// Chốt chỉ số của pool đến `now` rồi cộng phần thưởng từ lần tích lũy trước
+    /// vào `pending_reward`. Phải gọi trước mọi thay đổi số dư để không mất phần thưởng.
+    /// Khóa đã hết hạn thì hệ số trở về mức cơ bản từ đây.
+    pub fn accrue(&mut self, config: &mut StakeConfig, now: i64) -> Result<()> {
+        config.checkpoint(now)?;
+        let reward = self.reward_until(config.reward_index, config.apr_index, now)?;
+        self.pending_reward = self.pending_reward.checked_add(reward).ok_or(AppError::MathOverflow)?;
+        config.total_liabilities = config.total_liabilities.checked_add(reward).ok_or(AppError::MathOverflow)?;
+        self.reward_index_snapshot = config.reward_index;
+        self.apr_index_snapshot = config.apr_index;
+        self.stake_at = now;
+        if !self.is_locked(now) {
+            self.multiplier_bps = BASE_MULTIPLIER_BPS;
+        }
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, config: &mut StakeConfig, amount: u64, now: i64) -> Result<()> {
+        self.accrue(config, now)?;
+        self.amount = self.amount.checked_add(amount).ok_or(AppError::MathOverflow)?;
+        self.is_staked = true;
+        Ok(())
+    }
+
+    /// Khóa vị thế theo mức `lock_days`, gọi sau `accrue`. Nạp thêm không bao giờ
+    /// rút ngắn khóa đang chạy hay giảm hệ số của nó; khóa đã hết hạn thì hệ số cũ
+    /// không còn, chỉ mức mới được tính.
+    pub fn lock(&mut self, lock_days: u16, now: i64) -> Result<()> {
+        let (_, multiplier_bps) = LOCK_TIERS
+            .iter()
+            .find(|(days, _)| *days == lock_days)
+            .ok_or(AppError::InvalidLockTier)?;
+        let lock_until = now
+            .checked_add(lock_days as i64 * SECONDS_PER_DAY)
+            .ok_or(AppError::MathOverflow)?;
+
+        if !self.is_locked(now) {
+            self.multiplier_bps = BASE_MULTIPLIER_BPS;
+        }
+        self.lock_until = self.lock_until.max(lock_until);
+        self.multiplier_bps = self.multiplier_bps.max(*multiplier_bps);
+        Ok(())
+    }
+
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.lock_until
+    }
+
+    pub fn withdraw(&mut self, config: &mut StakeConfig, amount: u64, now: i64) -> Result<()> {
+        self.accrue(config, now)?;
+        self.amount = self.amount.checked_sub(amount).ok_or(AppError::MathOverflow)?;
+        self.is_staked = self.amount > 0;
+        Ok(())
+    }
+
+    /// Chuyển `amount` sang trạng thái unbonding, trả về id của ticket mới.
+    pub fn start_unbonding(&mut self, config: &mut StakeConfig, amount: u64, now: i64) -> Result<u64> {
+        self.withdraw(config, amount, now)?;
+        self.unbonding_amount = self.unbonding_amount.checked_add(amount).ok_or(AppError::MathOverflow)?;
+        let ticket_id = self.next_ticket_id;
+        self.next_ticket_id = ticket_id.checked_add(1).ok_or(AppError::MathOverflow)?;
+        Ok(ticket_id)
+    }
+
+    pub fn finish_unbonding(&mut self, amount: u64) -> Result<()> {
+        self.unbonding_amount = self.unbonding_amount.checked_sub(amount).ok_or(AppError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Lấy phần thưởng đang chờ để trả, tối đa `available` (số dư reward vault).
+    /// Phần chưa trả được vẫn nằm trong `pending_reward` như một khoản nợ để claim sau.
+    pub fn take_reward(&mut self, config: &mut StakeConfig, available: u64) -> Result<u64> {
+        let paid = self.pending_reward.min(available);
+        self.pending_reward -= paid;
+        config.total_liabilities = config.total_liabilities.checked_sub(paid).ok_or(AppError::MathOverflow)?;
+        Ok(paid)
+    }
+
+    /// Rút khẩn cấp: bỏ toàn bộ phần thưởng (kể cả phần chưa tích lũy) và trả về
+    /// số token gốc cần hoàn lại. Chỉ dùng được khi admin đã tạm dừng pool, nếu không
+    /// staker có thể lách thời gian khóa và phạt rút sớm của `unstake`.
+    pub fn forfeit(&mut self, config: &mut StakeConfig) -> Result<u64> {
+        if !config.paused {
+            return Err(AppError::NotPaused.into());
+        }
+        let forfeited = std::mem::take(&mut self.pending_reward);
+        config.total_liabilities = config.total_liabilities.checked_sub(forfeited).ok_or(AppError::MathOverflow)?;
+        self.is_staked = false;
+        Ok(std::mem::take(&mut self.amount))
+    }
+
+    /// Gộp `source` vào vị thế này. Hệ số nhân lấy trung bình theo số token
+    /// để không ai tăng được hệ số bằng cách gộp, còn thời gian khóa lấy mốc muộn hơn.
+    /// Cả hai được tích lũy trước nên vị thế đã hết hạn khóa chỉ góp hệ số cơ bản.
+    pub fn merge(&mut self, config: &mut StakeConfig, source: &mut StakeInfo, now: i64) -> Result<()> {
+        self.accrue(config, now)?;
+        source.accrue(config, now)?;
+
+        let amount = self.amount.checked_add(source.amount).ok_or(AppError::MathOverflow)?;
+        if amount > 0 {
+            let weighted = self.amount as u128 * self.multiplier_bps as u128
+                + source.amount as u128 * source.multiplier_bps as u128;
+            self.multiplier_bps = (weighted / amount as u128) as u64;
+        } else {
+            self.multiplier_bps = self.multiplier_bps.max(source.multiplier_bps);
+        }
+        self.amount = amount;
+        self.is_staked = amount > 0;
+        self.lock_until = self.lock_until.max(source.lock_until);
+        // Chỉ chuyển chỗ, tổng nợ của pool không đổi
+        self.pending_reward = self
+            .pending_reward
+            .checked_add(std::mem::take(&mut source.pending_reward))
+            .ok_or(AppError::MathOverflow)?;
+
+        source.amount = 0;
+        source.is_staked = false;
+        Ok(())
+    }
+
+    pub fn has_position_nft(&self) -> bool {
+        self.position_mint != Pubkey::default()
+    }
+
+    /// Kiểm tra `signer` có quyền với vị thế: là `staker` nếu vị thế chưa có NFT,
+    /// hoặc đang giữ NFT trong `position_token` nếu đã có. Chỉ có tác dụng với
+    /// instruction tìm vị thế theo `stake_info.staker` (như `top_up`, `claim`);
+    /// `stake` suy ra địa chỉ từ người ký nên người giữ NFT không dùng được.
+    pub fn authorize(&self, signer: Pubkey, position_token: Option<&TokenAccount>) -> Result<()> {
+        let authorized = if self.has_position_nft() {
+            position_token.is_some_and(|token| {
+                token.mint == self.position_mint && token.owner == signer && token.amount == 1
+            })
+        } else {
+            signer == self.staker
+        };
+        if !authorized {
+            return Err(AppError::Unauthorized.into());
+        }
+        Ok(())
+    }
+
+    /// Vault còn giữ token gốc của staker (đang stake hoặc đang unbonding).
+    pub fn has_principal(&self) -> bool {
+        self.amount > 0 || self.unbonding_amount > 0
+    }
+
+    /// Không còn token gốc lẫn phần thưởng nợ thì có thể đóng tài khoản.
+    pub fn is_empty(&self) -> bool {
+        !self.has_principal() && self.pending_reward == 0
+    }
+
+    /// Đóng được ngay khi rỗng. Vị thế có NFT chỉ đóng qua `close_position`, nơi
+    /// NFT bị đốt cùng lúc để không trỏ tới tài khoản đã đóng.
+    pub fn can_close(&self) -> bool {
+        self.is_empty() && !self.has_position_nft()
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakeConfig {
+    pub admin: Pubkey,
+
+    pub stake_mint: Pubkey,
+
+    pub reward_mint: Pubkey,
+
+    /// Phần thưởng mỗi kỳ, tính bằng basis point của số token đang stake
+    /// (đơn vị nhỏ nhất của reward mint trên mỗi đơn vị nhỏ nhất của stake mint).
+    pub reward_rate_bps: u64,
+
+    /// Độ dài một kỳ, tính bằng giây (1 nghĩa là `reward_rate_bps` mỗi giây).
+    pub period_length: u64,
+
+    /// APR tối đa (basis point); phần thưởng không bao giờ vượt quá mức này,
+    /// kể cả khi đã nhân hệ số khóa.
+    pub max_apr_bps: u64,
+
+    /// Phạt khi rút trước hạn khóa (basis point của số token rút), chuyển vào
+    /// reward vault. Bằng 0 thì không cho rút trước hạn.
+    pub early_unstake_penalty_bps: u64,
+
+    /// Tổng phần thưởng đã ghi nhận cho các staker nhưng chưa trả.
+    pub total_liabilities: u64,
+
+    /// Thời gian chờ (giây) giữa `request_unstake` và `withdraw_unbonded`.
+    pub unbonding_period: u64,
+
+    /// NFT vị thế có chuyển nhượng được không. Cố định từ lúc khởi tạo pool.
+    pub transferable_positions: bool,
+
+    /// Công tắc khẩn cấp của admin: chặn stake và trả thưởng, chỉ cho rút gốc.
+    pub paused: bool,
+
+    pub bump: u8,
+
+    /// Phần thưởng cộng dồn trên mỗi token stake (chưa nhân hệ số khóa), nhân với
+    /// `REWARD_INDEX_PRECISION`. Được chốt trước mỗi lần đổi lãi suất để thời gian
+    /// đã qua giữ nguyên giá cũ.
+    pub reward_index: u128,
+
+    /// Giới hạn APR cộng dồn trên mỗi token stake, cùng đơn vị với `reward_index`.
+    pub apr_index: u128,
+
+    /// Thời điểm (unix timestamp) hai chỉ số được chốt lần cuối.
+    pub index_updated_at: i64,
+}
+
+/// Một lần rút đang chờ hết thời gian unbonding, giống stake đang deactivate
+/// của Solana. Mỗi staker có thể có nhiều ticket cùng lúc.
+#[account]
+#[derive(InitSpace)]
+pub struct UnbondingTicket {
+    pub staker: Pubkey,
+
+    pub stake_info: Pubkey,
+
+    pub id: u64,
+
+    pub amount: u64,
+
+    /// Rút được từ thời điểm này (unix timestamp).
+    pub release_at: i64,
+
+    pub bump: u8,
+}
+
+impl UnbondingTicket {
+    pub fn is_released(&self, now: i64) -> bool {
+        now >= self.release_at
+    }
+}
+
+impl StakeConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.period_length == 0 {
+            return Err(AppError::InvalidPeriod.into());
+        }
+        // Token phạt là stake mint nên chỉ chuyển vào reward vault được khi hai mint trùng nhau
+        if self.early_unstake_penalty_bps > BPS_DENOMINATOR
+            || (self.early_unstake_penalty_bps > 0 && self.stake_mint != self.reward_mint)
+        {
+            return Err(AppError::InvalidPenalty.into());
+        }
+        Ok(())
+    }
+
+    /// Hai chỉ số `(reward_index, apr_index)` nếu chốt tại `now`, không thay đổi trạng thái.
+    pub fn indices_at(&self, now: i64) -> Result<(u128, u128)> {
+        let elapsed = now.saturating_sub(self.index_updated_at).max(0) as u128;
+        let bps = BPS_DENOMINATOR as u128;
+        let reward_index = (self.reward_rate_bps as u128)
+            .checked_mul(elapsed)
+            .and_then(|value| value.checked_mul(REWARD_INDEX_PRECISION))
+            .map(|value| value / (self.period_length as u128 * bps))
+            .and_then(|value| value.checked_add(self.reward_index))
+            .ok_or(AppError::MathOverflow)?;
+        let apr_index = (self.max_apr_bps as u128)
+            .checked_mul(elapsed)
+            .and_then(|value| value.checked_mul(REWARD_INDEX_PRECISION))
+            .map(|value| value / (bps * SECONDS_PER_YEAR as u128))
+            .and_then(|value| value.checked_add(self.apr_index))
+            .ok_or(AppError::MathOverflow)?;
+        Ok((reward_index, apr_index))
+    }
+
+    /// Chốt hai chỉ số đến `now` theo lãi suất hiện tại. Gọi trước khi đổi
+    /// `reward_rate_bps`, `period_length` hoặc `max_apr_bps`.
+    pub fn checkpoint(&mut self, now: i64) -> Result<()> {
+        (self.reward_index, self.apr_index) = self.indices_at(now)?;
+        self.index_updated_at = self.index_updated_at.max(now);
+        Ok(())
+    }
+
+    pub fn early_unstake_penalty(&self, amount: u64) -> u64 {
+        // penalty_bps <= BPS_DENOMINATOR (xem `validate`) nên kết quả không vượt quá `amount`
+        (amount as u128 * self.early_unstake_penalty_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Rút ngay chỉ dùng được khi pool không có thời gian chờ; ngược lại phải đi
+    /// qua `request_unstake` để không ai bỏ qua được `unbonding_period`.
+    pub fn check_instant_unstake(&self) -> Result<()> {
+        if self.unbonding_period > 0 {
+            return Err(AppError::UnbondingRequired.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StakeConfig {
+        StakeConfig {
+            admin: Pubkey::default(),
+            stake_mint: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            reward_rate_bps: 100,
+            period_length: 10,
+            max_apr_bps: u32::MAX as u64,
+            early_unstake_penalty_bps: 0,
+            unbonding_period: 0,
+            transferable_positions: false,
+            total_liabilities: 0,
+            paused: false,
+            bump: 0,
+            reward_index: 0,
+            apr_index: 0,
+            index_updated_at: 0,
+        }
+    }
+
+    fn stake_info() -> StakeInfo {
+        StakeInfo {
+            staker: Pubkey::default(),
+            position_index: 0,
+            stake_mint: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            stake_at: 0,
+            is_staked: false,
+            amount: 0,
+            pending_reward: 0,
+            lock_until: 0,
+            multiplier_bps: 10_000,
+            unbonding_amount: 0,
+            next_ticket_id: 0,
+            position_mint: Pubkey::default(),
+            reward_index_snapshot: 0,
+            apr_index_snapshot: 0,
+        }
+    }
+
+    /// Phần thưởng `amount` token nhận sau `elapsed` giây, tính qua đúng đường của
+    /// chương trình: `accrue` một vị thế vừa tích lũy ở lần chốt cuối của pool.
+    fn try_earned(config: &StakeConfig, amount: u64, elapsed: i64, multiplier_bps: u64) -> Result<u64> {
+        let mut config = config.clone();
+        let start = config.index_updated_at;
+        let mut info = StakeInfo {
+            amount,
+            multiplier_bps,
+            lock_until: i64::MAX,
+            stake_at: start,
+            reward_index_snapshot: config.reward_index,
+            apr_index_snapshot: config.apr_index,
+            ..stake_info()
+        };
+        info.accrue(&mut config, start.checked_add(elapsed).ok_or(AppError::MathOverflow)?)?;
+        Ok(info.pending_reward)
+    }
+
+    fn earned(config: &StakeConfig, amount: u64, elapsed: i64, multiplier_bps: u64) -> u64 {
+        try_earned(config, amount, elapsed, multiplier_bps).unwrap()
+    }
+
+    #[test]
+    fn top_up_keeps_rewards_earned_before_it() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 100).unwrap();
+        info.deposit(&mut config, 1_000_000, 200).unwrap();
+        info.accrue(&mut config, 300).unwrap();
+
+        let expected =
+            earned(&config, 1_000_000, 100, 10_000) + earned(&config, 2_000_000, 100, 10_000);
+        assert_eq!(info.pending_reward, expected);
+        assert_eq!(info.amount, 2_000_000);
+    }
+
+    #[test]
+    fn partial_unstake_keeps_rewards_on_the_remaining_balance() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.withdraw(&mut config, 400_000, 100).unwrap();
+        // Phần thưởng của cả 1_000_000 token trong 100 giây đầu vẫn còn
+        assert_eq!(info.pending_reward, earned(&config, 1_000_000, 100, 10_000));
+
+        info.accrue(&mut config, 200).unwrap();
+        let expected =
+            earned(&config, 1_000_000, 100, 10_000) + earned(&config, 600_000, 100, 10_000);
+        assert_eq!(info.pending_reward, expected);
+        assert!(info.is_staked);
+    }
+
+    #[test]
+    fn claiming_resets_pending_but_not_principal() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.accrue(&mut config, 100).unwrap();
+        let claimed = info.take_reward(&mut config, u64::MAX).unwrap();
+
+        assert_eq!(claimed, earned(&config, 1_000_000, 100, 10_000));
+        assert_eq!(info.pending_reward, 0);
+        assert_eq!(info.amount, 1_000_000);
+
+        info.accrue(&mut config, 200).unwrap();
+        assert_eq!(info.pending_reward, earned(&config, 1_000_000, 100, 10_000));
+    }
+
+    #[test]
+    fn full_unstake_leaves_rewards_to_pay_out() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.withdraw(&mut config, 1_000_000, 100).unwrap();
+
+        assert_eq!(info.amount, 0);
+        assert!(!info.is_staked);
+        let owed = earned(&config, 1_000_000, 100, 10_000);
+        assert_eq!(info.take_reward(&mut config, u64::MAX).unwrap(), owed);
+    }
+
+    #[test]
+    fn multiplier_is_applied_before_rounding() {
+        let mut config = config();
+        config.reward_rate_bps = 1;
+        config.period_length = 1;
+
+        // 15 * 1 * 1 / 10_000 làm tròn về 0 nếu chia trước khi nhân hệ số
+        assert_eq!(earned(&config, 15_000, 1, 15_000), 2);
+        assert_eq!(earned(&config, 15_000, 1, 10_000), 1);
+    }
+
+    #[test]
+    fn rate_change_only_reprices_future_time() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        let before = earned(&config, 1_000_000, 100, 10_000);
+
+        // Admin tăng gấp đôi lãi suất giữa kỳ, staker chưa tích lũy lần nào
+        config.checkpoint(100).unwrap();
+        config.reward_rate_bps = 200;
+        info.accrue(&mut config, 200).unwrap();
+
+        let after = earned(&config, 1_000_000, 100, 10_000);
+        assert_eq!(after, 2 * before);
+        assert_eq!(info.pending_reward, before + after);
+        assert_eq!(config.total_liabilities, before + after);
+    }
+
+    #[test]
+    fn zeroing_the_rate_keeps_rewards_already_earned() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        let owed = earned(&config, 1_000_000, 100, 10_000);
+        config.checkpoint(100).unwrap();
+        config.reward_rate_bps = 0;
+
+        assert_eq!(info.unaccrued_reward(&config, 300).unwrap(), owed);
+        info.accrue(&mut config, 300).unwrap();
+        assert_eq!(info.pending_reward, owed);
+    }
+
+    #[test]
+    fn lock_tier_multiplies_rewards() {
+        let mut config = config();
+        let mut info = stake_info();
+        info.lock(180, 0).unwrap();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.accrue(&mut config, 100).unwrap();
+
+        assert_eq!(info.pending_reward, 2 * earned(&config, 1_000_000, 100, 10_000));
+    }
+
+    #[test]
+    fn expired_lock_stops_boosting_a_top_up() {
+        let mut config = config();
+        let mut info = stake_info();
+        let expiry = 180 * SECONDS_PER_DAY;
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.lock(180, 0).unwrap();
+
+        // Hết hạn khóa rồi mới nạp thêm một khoản lớn mà không khóa
+        info.deposit(&mut config, 1_000_000_000, expiry + 20 * SECONDS_PER_DAY).unwrap();
+        info.lock(0, expiry + 20 * SECONDS_PER_DAY).unwrap();
+        assert_eq!(info.multiplier_bps, BASE_MULTIPLIER_BPS);
+        // 2x đến lúc hết hạn, 1x cho 20 ngày sau đó
+        let before_top_up =
+            earned(&config, 1_000_000, expiry, 20_000) + earned(&config, 1_000_000, 20 * SECONDS_PER_DAY, 10_000);
+        assert_eq!(info.pending_reward, before_top_up);
+
+        info.accrue(&mut config, expiry + 30 * SECONDS_PER_DAY).unwrap();
+        assert_eq!(
+            info.pending_reward,
+            before_top_up + earned(&config, 1_001_000_000, 10 * SECONDS_PER_DAY, 10_000)
+        );
+    }
+
+    #[test]
+    fn simulated_reward_stops_boosting_at_expiry() {
+        let mut config = config();
+        let mut info = stake_info();
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.lock(30, 0).unwrap();
+
+        let expiry = 30 * SECONDS_PER_DAY;
+        let expected = earned(&config, 1_000_000, expiry, 12_500) + earned(&config, 1_000_000, expiry, 10_000);
+        assert_eq!(info.unaccrued_reward(&config, 2 * expiry).unwrap(), expected);
+    }
+
+    #[test]
+    fn top_up_never_shortens_the_lock() {
+        let mut info = stake_info();
+        info.lock(90, 0).unwrap();
+        info.lock(0, SECONDS_PER_DAY).unwrap();
+
+        assert_eq!(info.lock_until, 90 * SECONDS_PER_DAY);
+        assert_eq!(info.multiplier_bps, 15_000);
+        assert!(info.is_locked(90 * SECONDS_PER_DAY - 1));
+        assert!(!info.is_locked(90 * SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn unknown_lock_tier_is_rejected() {
+        let mut info = stake_info();
+        assert!(info.lock(45, 0).is_err());
+    }
+
+    #[test]
+    fn penalty_needs_matching_mints() {
+        let mut config = config();
+        config.early_unstake_penalty_bps = 1_000;
+        config.reward_mint = Pubkey::new_unique();
+        assert!(config.validate().is_err());
+
+        config.reward_mint = config.stake_mint;
+        assert!(config.validate().is_ok());
+        assert_eq!(config.early_unstake_penalty(1_000_000), 100_000);
+    }
+
+    #[test]
+    fn short_reward_vault_leaves_an_iou() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.withdraw(&mut config, 1_000_000, 100).unwrap();
+        let owed = earned(&config, 1_000_000, 100, 10_000);
+        assert_eq!(config.total_liabilities, owed);
+
+        // Vault chỉ còn một nửa: trả một nửa, phần còn lại vẫn là nợ
+        let paid = info.take_reward(&mut config, owed / 2).unwrap();
+        assert_eq!(paid, owed / 2);
+        assert_eq!(info.pending_reward, owed - owed / 2);
+        assert_eq!(config.total_liabilities, owed - owed / 2);
+        assert!(!info.is_empty());
+
+        info.take_reward(&mut config, u64::MAX).unwrap();
+        assert!(info.is_empty());
+        assert_eq!(config.total_liabilities, 0);
+    }
+
+    #[test]
+    fn instant_unstake_is_refused_while_unbonding_is_configured() {
+        let mut config = config();
+        assert!(config.check_instant_unstake().is_ok());
+
+        config.unbonding_period = 7 * SECONDS_PER_DAY as u64;
+        assert_eq!(config.check_instant_unstake().unwrap_err(), AppError::UnbondingRequired.into());
+    }
+
+    #[test]
+    fn forfeit_returns_principal_and_drops_rewards() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.accrue(&mut config, 100).unwrap();
+        assert!(config.total_liabilities > 0);
+
+        config.paused = true;
+
+        assert_eq!(info.forfeit(&mut config).unwrap(), 1_000_000);
+        assert!(info.is_empty());
+        assert!(!info.is_staked);
+        assert_eq!(config.total_liabilities, 0);
+    }
+
+    #[test]
+    fn forfeit_is_rejected_while_the_pool_runs() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.lock(90, 0).unwrap();
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.accrue(&mut config, 100).unwrap();
+        let owed = info.pending_reward;
+
+        assert!(info.is_locked(100));
+        assert_eq!(info.forfeit(&mut config).unwrap_err(), AppError::NotPaused.into());
+        // Không có gì thay đổi: token vẫn khóa, phần thưởng vẫn còn
+        assert_eq!(info.amount, 1_000_000);
+        assert_eq!(info.pending_reward, owed);
+        assert_eq!(config.total_liabilities, owed);
+    }
+
+    #[test]
+    fn simulated_reward_matches_accrual() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        info.accrue(&mut config, 100).unwrap();
+        let simulated = info.pending_reward + info.unaccrued_reward(&config, 250).unwrap();
+
+        info.accrue(&mut config, 250).unwrap();
+        assert_eq!(info.pending_reward, simulated);
+        // Đồng hồ lùi lại không làm phần thưởng âm
+        assert_eq!(info.unaccrued_reward(&config, 200).unwrap(), 0);
+    }
+
+    #[test]
+    fn unbonding_stops_rewards_but_keeps_principal() {
+        let mut config = config();
+        let mut info = stake_info();
+
+        info.deposit(&mut config, 1_000_000, 0).unwrap();
+        assert_eq!(info.start_unbonding(&mut config, 400_000, 100).unwrap(), 0);
+        assert_eq!(info.start_unbonding(&mut config, 600_000, 100).unwrap(), 1);
+        assert!(!info.is_staked);
+        assert_eq!(info.unbonding_amount, 1_000_000);
+
+        // Token đang unbonding không sinh thêm thưởng
+        info.accrue(&mut config, 200).unwrap();
+        assert_eq!(info.pending_reward, earned(&config, 1_000_000, 100, 10_000));
+
+        info.take_reward(&mut config, u64::MAX).unwrap();
+        info.finish_unbonding(400_000).unwrap();
+        assert!(info.has_principal());
+        info.finish_unbonding(600_000).unwrap();
+        assert!(info.is_empty());
+    }
+
+    #[test]
+    fn merge_weights_multiplier_and_keeps_rewards() {
+        let mut config = config();
+        let mut target = stake_info();
+        let mut source = stake_info();
+
+        target.lock(180, 0).unwrap();
+        target.deposit(&mut config, 1_000_000, 0).unwrap();
+        source.lock(30, 0).unwrap();
+        source.deposit(&mut config, 3_000_000, 0).unwrap();
+
+        target.merge(&mut config, &mut source, 100).unwrap();
+        let earned =
+            earned(&config, 1_000_000, 100, 20_000) + earned(&config, 3_000_000, 100, 12_500);
+
+        assert_eq!(target.amount, 4_000_000);
+        assert_eq!(target.pending_reward, earned);
+        assert_eq!(config.total_liabilities, earned);
+        // (1 * 20_000 + 3 * 12_500) / 4
+        assert_eq!(target.multiplier_bps, 14_375);
+        assert_eq!(target.lock_until, 180 * SECONDS_PER_DAY);
+        assert!(source.is_empty());
+    }
+
+    #[test]
+    fn merging_an_expired_source_drops_its_multiplier() {
+        let mut config = config();
+        let mut target = stake_info();
+        let mut source = stake_info();
+        let expiry = 180 * SECONDS_PER_DAY;
+
+        target.deposit(&mut config, 3_000_000, 0).unwrap();
+        target.lock(0, 0).unwrap();
+        source.deposit(&mut config, 1_000_000, 0).unwrap();
+        source.lock(180, 0).unwrap();
+
+        let now = expiry + 20 * SECONDS_PER_DAY;
+        target.merge(&mut config, &mut source, now).unwrap();
+
+        assert_eq!(target.multiplier_bps, BASE_MULTIPLIER_BPS);
+        assert!(!target.is_locked(now));
+        let earned_before = earned(&config, 3_000_000, now, 10_000)
+            + earned(&config, 1_000_000, expiry, 20_000)
+            + earned(&config, 1_000_000, 20 * SECONDS_PER_DAY, 10_000);
+        assert_eq!(target.pending_reward, earned_before);
+
+        target.accrue(&mut config, now + 100).unwrap();
+        assert_eq!(target.pending_reward, earned_before + earned(&config, 4_000_000, 100, 10_000));
+    }
+
+    #[test]
+    fn merged_nft_source_is_left_for_close_position() {
+        let mut config = config();
+        let mut target = stake_info();
+        let mut source = stake_info();
+        source.position_mint = Pubkey::new_unique();
+
+        target.deposit(&mut config, 1_000_000, 0).unwrap();
+        source.deposit(&mut config, 1_000_000, 0).unwrap();
+        target.merge(&mut config, &mut source, 100).unwrap();
+
+        assert_eq!(target.amount, 2_000_000);
+        // Rỗng nhưng còn NFT: không đóng tại chỗ mà chờ đốt NFT
+        assert!(source.is_empty());
+        assert!(!source.can_close());
+    }
+
+    fn position_token(mint: Pubkey, owner: Pubkey) -> TokenAccount {
+        use anchor_spl::token_interface::spl_token_2022::solana_program::program_pack::Pack;
+        use anchor_spl::token_interface::spl_token_2022::state::{Account, AccountState};
+
+        let mut data = [0; Account::LEN];
+        Account::pack(
+            Account {
+                mint,
+                owner,
+                amount: 1,
+                state: AccountState::Initialized,
+                ..Account::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        TokenAccount::try_deserialize_unchecked(&mut data.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn position_nft_moves_authority_to_the_holder() {
+        let mut info = stake_info();
+        info.staker = Pubkey::new_unique();
+        assert!(info.authorize(info.staker, None).is_ok());
+        assert!(info.authorize(Pubkey::new_unique(), None).is_err());
+
+        info.position_mint = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let token = position_token(info.position_mint, buyer);
+
+        assert!(info.authorize(buyer, Some(&token)).is_ok());
+        // Người stake ban đầu đã bán NFT thì mất quyền
+        assert!(info.authorize(info.staker, None).is_err());
+        assert!(info.authorize(info.staker, Some(&token)).is_err());
+        assert!(!info.can_close());
+    }
+
+    mod reward_properties {
+        use super::*;
+        use crate::constants::SECONDS_PER_YEAR;
+        use proptest::prelude::*;
+
+        const HUNDRED_YEARS: i64 = 100 * SECONDS_PER_YEAR as i64;
+
+        fn pool(reward_rate_bps: u64, period_length: u64, max_apr_bps: u64) -> StakeConfig {
+            StakeConfig {
+                reward_rate_bps,
+                period_length,
+                max_apr_bps,
+                ..config()
+            }
+        }
+
+        fn multiplier() -> impl Strategy<Value = u64> {
+            prop::sample::select(LOCK_TIERS.iter().map(|(_, multiplier_bps)| *multiplier_bps).collect::<Vec<_>>())
+        }
+
+        proptest! {
+            #[test]
+            fn never_panics(amount: u64,
+                            elapsed in 0..=i64::MAX,
+                            reward_rate_bps: u64,
+                            period_length in 1..=u64::MAX,
+                            max_apr_bps: u64,
+                            multiplier_bps in multiplier()) {
+                let config = pool(reward_rate_bps, period_length, max_apr_bps);
+                if let Err(error) = try_earned(&config, amount, elapsed, multiplier_bps) {
+                    prop_assert_eq!(error, AppError::MathOverflow.into());
+                }
+            }
+
+            #[test]
+            fn stays_under_the_apr_cap(amount: u64,
+                                       elapsed in 0..=HUNDRED_YEARS,
+                                       reward_rate_bps in 0..=1_000_000u64,
+                                       period_length in 1..=SECONDS_PER_YEAR,
+                                       max_apr_bps in 0..=1_000_000u64,
+                                       multiplier_bps in multiplier()) {
+                let config = pool(reward_rate_bps, period_length, max_apr_bps);
+                let cap = amount as u128 * max_apr_bps as u128 * elapsed as u128
+                    / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+
+                match try_earned(&config, amount, elapsed, multiplier_bps) {
+                    Ok(reward) => prop_assert!(reward as u128 <= cap),
+                    // Chỉ lỗi khi phần thưởng thật sự không vừa u64
+                    Err(_) => prop_assert!(cap > u64::MAX as u128),
+                }
+            }
+
+            #[test]
+            fn grows_with_time(amount: u64,
+                               first in 0..=HUNDRED_YEARS,
+                               second in 0..=HUNDRED_YEARS,
+                               reward_rate_bps in 0..=1_000_000u64,
+                               max_apr_bps in 0..=1_000_000u64,
+                               multiplier_bps in multiplier()) {
+                let mut config = pool(reward_rate_bps, 1, max_apr_bps);
+                let mut info = stake_info();
+                info.lock_until = i64::MAX;
+                info.deposit(&mut config, amount, 0).unwrap();
+                info.multiplier_bps = multiplier_bps;
+                let (shorter, longer) = (first.min(second), first.max(second));
+
+                if let Ok(longer_reward) = info.unaccrued_reward(&config, longer) {
+                    prop_assert!(info.unaccrued_reward(&config, shorter).unwrap() <= longer_reward);
+                }
+            }
+
+            #[test]
+            fn splitting_accrual_never_pays_more(amount: u64,
+                                                 first in 0..=HUNDRED_YEARS,
+                                                 second in 0..=HUNDRED_YEARS,
+                                                 reward_rate_bps in 0..=1_000_000u64,
+                                                 period_length in 1..=SECONDS_PER_YEAR,
+                                                 multiplier_bps in multiplier()) {
+                let mut config = pool(reward_rate_bps, period_length, 1_000_000);
+                let mut info = stake_info();
+                info.lock_until = i64::MAX;
+                info.deposit(&mut config, amount, 0).unwrap();
+                info.multiplier_bps = multiplier_bps;
+                let (mut whole_config, mut whole) = (config.clone(), info.clone());
+
+                // Chốt chỉ số ở giữa (như khi một staker khác tích lũy) rồi tích lũy hai lần
+                if whole.accrue(&mut whole_config, first + second).is_ok() {
+                    config.checkpoint(first).unwrap();
+                    info.accrue(&mut config, first).unwrap();
+                    info.accrue(&mut config, first + second).unwrap();
+                    prop_assert!(info.pending_reward <= whole.pending_reward);
+                }
+            }
+        }
+    }
 }
@@ -11,6 +11,8 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createMintToInstruction,
   getAccount,
+  getMint,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
 import { expect } from "chai";
 import { BN } from "bn.js";
@@ -23,12 +25,75 @@ describe("stake-program", () => {
 
   const staker = anchor.web3.Keypair.generate();
   let stakerTokenAccount: anchor.web3.PublicKey;
+  let stakerRewardAccount: anchor.web3.PublicKey;
+  let adminRewardAccount: anchor.web3.PublicKey;
 
-  // Fake USDC mint
-  const usdcMintKp = anchor.web3.Keypair.generate();
+  // Fake LP mint (stake) and governance mint (reward)
+  const stakeMintKp = anchor.web3.Keypair.generate();
+  const rewardMintKp = anchor.web3.Keypair.generate();
   let rewardVault: anchor.web3.PublicKey;
+  let stakeVault: anchor.web3.PublicKey;
+  let config: anchor.web3.PublicKey;
+  const programData = anchor.web3.PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  )[0];
   let stakeInfo: anchor.web3.PublicKey;
 
+  const poolSeeds = () => [
+    stakeMintKp.publicKey.toBuffer(),
+    rewardMintKp.publicKey.toBuffer(),
+  ];
+
+  // Mỗi staker có nhiều vị thế trong một pool, phân biệt bằng index (u64 LE)
+  const positionPda = (index: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("stake_info"),
+        staker.publicKey.toBuffer(),
+        ...poolSeeds(),
+        new BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const createMintIxs = async (mint: anchor.web3.PublicKey) => {
+    const lamports = await getMinimumBalanceForRentExemptMint(
+      provider.connection
+    );
+
+    return [
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: provider.publicKey,
+        newAccountPubkey: mint,
+        space: MINT_SIZE,
+        lamports,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(
+        mint,
+        6,
+        provider.publicKey,
+        provider.publicKey,
+        TOKEN_PROGRAM_ID
+      ),
+    ];
+  };
+
+  const initializeAccounts = () => ({
+    admin: provider.publicKey,
+    program: program.programId,
+    programData,
+    stakeMint: stakeMintKp.publicKey,
+    rewardMint: rewardMintKp.publicKey,
+    config,
+    rewardVault,
+    stakeVault,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    stakeTokenProgram: TOKEN_PROGRAM_ID,
+    rewardTokenProgram: TOKEN_PROGRAM_ID,
+  });
+
   before(async () => {
     // Airdrop SOL to staker
     await provider.connection.confirmTransaction(
@@ -38,73 +103,69 @@ describe("stake-program", () => {
       )
     );
 
-    // Create fake USDC mint
-    const tx = new anchor.web3.Transaction();
-    const lamports = await getMinimumBalanceForRentExemptMint(
-      provider.connection
-    );
-
-    const createMintIx = anchor.web3.SystemProgram.createAccount({
-      fromPubkey: provider.publicKey,
-      newAccountPubkey: usdcMintKp.publicKey,
-      space: MINT_SIZE,
-      lamports,
-      programId: TOKEN_PROGRAM_ID,
-    });
-
-    const initMintIx = createInitializeMint2Instruction(
-      usdcMintKp.publicKey,
-      6,
-      provider.publicKey,
-      provider.publicKey,
-      TOKEN_PROGRAM_ID
-    );
-
     stakerTokenAccount = getAssociatedTokenAddressSync(
-      usdcMintKp.publicKey,
+      stakeMintKp.publicKey,
       staker.publicKey
     );
-
-    const createStakerTokenAccountIx = createAssociatedTokenAccountInstruction(
-      staker.publicKey,
-      stakerTokenAccount,
-      staker.publicKey,
-      usdcMintKp.publicKey
+    stakerRewardAccount = getAssociatedTokenAddressSync(
+      rewardMintKp.publicKey,
+      staker.publicKey
     );
-
-    const mintToStakerIx = createMintToInstruction(
-      usdcMintKp.publicKey,
-      stakerTokenAccount,
-      provider.publicKey,
-      1000 * 10 ** 6,
-      []
+    adminRewardAccount = getAssociatedTokenAddressSync(
+      rewardMintKp.publicKey,
+      provider.publicKey
     );
 
-    tx.add(
-      createMintIx,
-      initMintIx,
-      createStakerTokenAccountIx,
-      mintToStakerIx
+    const tx = new anchor.web3.Transaction().add(
+      ...(await createMintIxs(stakeMintKp.publicKey)),
+      ...(await createMintIxs(rewardMintKp.publicKey)),
+      createAssociatedTokenAccountInstruction(
+        staker.publicKey,
+        stakerTokenAccount,
+        staker.publicKey,
+        stakeMintKp.publicKey
+      ),
+      createMintToInstruction(
+        stakeMintKp.publicKey,
+        stakerTokenAccount,
+        provider.publicKey,
+        1000 * 10 ** 6,
+        []
+      )
     );
 
-    await provider.sendAndConfirm(tx, [usdcMintKp, staker]);
+    await provider.sendAndConfirm(tx, [stakeMintKp, rewardMintKp, staker]);
 
     rewardVault = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("reward"), usdcMintKp.publicKey.toBuffer()],
+      [Buffer.from("reward"), ...poolSeeds()],
+      program.programId
+    )[0];
+
+    config = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), ...poolSeeds()],
       program.programId
     )[0];
+
+    stakeVault = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stake_vault"), ...poolSeeds()],
+      program.programId
+    )[0];
+
+    stakeInfo = positionPda(0);
   });
 
   it("Is initialized!", async () => {
     const tx = await program.methods
-      .initialize()
-      .accounts({
-        admin: provider.publicKey,
-        rewardVault: rewardVault,
-        mint: usdcMintKp.publicKey,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      } as any)
+      // 1% mỗi giây, APR tối đa 10000%
+      .initialize(
+        new BN(100),
+        new BN(1),
+        new BN(1_000_000),
+        new BN(0),
+        new BN(0),
+        false
+      )
+      .accounts(initializeAccounts() as any)
       .rpc();
 
     console.log("Initialize TX:", tx);
@@ -115,36 +176,73 @@ describe("stake-program", () => {
     );
 
     expect(Number(rewardVaultAccount.amount)).to.equal(0);
+    expect(rewardVaultAccount.mint.toBase58()).to.equal(
+      rewardMintKp.publicKey.toBase58()
+    );
+
+    const configAccount = await program.account.stakeConfig.fetch(config);
+    expect(configAccount.admin.toBase58()).to.equal(
+      provider.publicKey.toBase58()
+    );
+    expect(configAccount.rewardRateBps.toNumber()).to.equal(100);
   });
 
-  it("Stake successfully", async () => {
-    stakeInfo = anchor.web3.PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("stake_info"),
-        staker.publicKey.toBuffer(),
-        usdcMintKp.publicKey.toBuffer(),
-      ],
-      program.programId
-    )[0];
+  it("Rejects a second initialize", async () => {
+    try {
+      await program.methods
+        .initialize(
+          new BN(100),
+          new BN(1),
+          new BN(1_000_000),
+          new BN(0),
+          new BN(0),
+          false
+        )
+        .accounts(initializeAccounts() as any)
+        .rpc();
+      throw new Error("Expected initialize to fail");
+    } catch (e) {
+      expect(e.message).to.not.equal("Expected initialize to fail");
+    }
+  });
 
-    const vaultTokenAccount = getAssociatedTokenAddressSync(
-      usdcMintKp.publicKey,
-      stakeInfo,
-      true
-    );
+  it("Rejects config updates from non-admins", async () => {
+    try {
+      await program.methods
+        .updateConfig(
+          new BN(1_000),
+          new BN(1),
+          new BN(1_000_000),
+          new BN(0),
+          new BN(0)
+        )
+        .accounts({ admin: staker.publicKey, config } as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected updateConfig to fail");
+    } catch (e) {
+      expect(e.message).to.include("Unauthorized");
+    }
+  });
+
+  it("Stake successfully", async () => {
 
     const stakeAmount = new BN(100 * 10 ** 6);
 
     const tx = await program.methods
-      .stake(stakeAmount)
+      // Không khóa, hệ số 1x
+      .stake(stakeAmount, 0, new BN(0))
       .accounts({
         staker: staker.publicKey,
-        mint: usdcMintKp.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
         stakeInfo: stakeInfo,
-        vaultTokenAccount,
+        positionTokenAccount: null,
+        stakeVault,
         stakerTokenAccount,
         systemProgram: anchor.web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       } as any)
       .signers([staker])
@@ -157,13 +255,18 @@ describe("stake-program", () => {
     expect(stakeInfoAccount.staker.toBase58()).to.equal(
       staker.publicKey.toBase58()
     );
-    expect(stakeInfoAccount.mint.toBase58()).to.equal(
-      usdcMintKp.publicKey.toBase58()
+    expect(stakeInfoAccount.stakeMint.toBase58()).to.equal(
+      stakeMintKp.publicKey.toBase58()
+    );
+    expect(stakeInfoAccount.rewardMint.toBase58()).to.equal(
+      rewardMintKp.publicKey.toBase58()
     );
     expect(stakeInfoAccount.isStaked).to.be.true;
     expect(stakeInfoAccount.amount.toString()).to.equal(
       stakeAmount.toString()
     );
+    expect(stakeInfoAccount.pendingReward.toNumber()).to.equal(0);
+    expect(stakeInfoAccount.multiplierBps.toNumber()).to.equal(10_000);
 
     const stakerAccount = await getAccount(
       provider.connection,
@@ -171,19 +274,25 @@ describe("stake-program", () => {
     );
     const vaultAccount = await getAccount(
       provider.connection,
-      vaultTokenAccount
+      stakeVault
     );
 
     expect(Number(stakerAccount.amount)).to.equal(900 * 10 ** 6);
     expect(Number(vaultAccount.amount)).to.equal(100 * 10 ** 6);
   });
 
-  it("Unstake successfully", async () => {
-    // Mint reward token to reward vault
+  it("Claim rewards without unstaking", async () => {
+    // Admin nạp token thưởng vào reward vault
     const mintRewardTx = new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(
+        provider.publicKey,
+        adminRewardAccount,
+        provider.publicKey,
+        rewardMintKp.publicKey
+      ),
       createMintToInstruction(
-        usdcMintKp.publicKey,
-        rewardVault,
+        rewardMintKp.publicKey,
+        adminRewardAccount,
         provider.publicKey,
         1000 * 10 ** 6,
         []
@@ -191,11 +300,86 @@ describe("stake-program", () => {
     );
     await provider.sendAndConfirm(mintRewardTx);
 
-    const vaultTokenAccount = getAssociatedTokenAddressSync(
-      usdcMintKp.publicKey,
-      stakeInfo,
-      true
+    await program.methods
+      .fundRewards(new BN(1000 * 10 ** 6))
+      .accounts({
+        admin: provider.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        rewardVault,
+        adminRewardAccount,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+
+    // Chờ ít nhất một giây để có phần thưởng
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const before = await program.account.stakeInfo.fetch(stakeInfo);
+    const simulated = await program.methods
+      .simulateReward()
+      .accounts({ config, stakeInfo } as any)
+      .view();
+    expect(simulated.toNumber()).to.be.greaterThan(0);
+
+    await program.methods
+      .claim()
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount: null,
+        rewardVault,
+        stakerRewardAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    const after = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(after.amount.toString()).to.equal(before.amount.toString());
+    expect(after.pendingReward.toNumber()).to.equal(0);
+    const configAccount = await program.account.stakeConfig.fetch(config);
+    expect(configAccount.totalLiabilities.toNumber()).to.equal(0);
+    expect(after.stakeAt.toNumber()).to.be.greaterThan(before.stakeAt.toNumber());
+
+    const stakerRewardAccountInfo = await getAccount(
+      provider.connection,
+      stakerRewardAccount
     );
+    // Claim chạy sau khi simulate nên nhận ít nhất số đã mô phỏng
+    expect(Number(stakerRewardAccountInfo.amount)).to.be.at.least(
+      simulated.toNumber()
+    );
+  });
+
+  it("Rejects reward funding from non-admins", async () => {
+    try {
+      await program.methods
+        .fundRewards(new BN(1))
+        .accounts({
+          admin: staker.publicKey,
+          stakeMint: stakeMintKp.publicKey,
+          rewardMint: rewardMintKp.publicKey,
+          config,
+          rewardVault,
+          adminRewardAccount,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected fundRewards to fail");
+    } catch (e) {
+      expect(e.message).to.include("Unauthorized");
+    }
+  });
+
+  it("Unstake successfully", async () => {
 
     const unstakeAmount = new BN(100 * 10 ** 6);
 
@@ -203,13 +387,18 @@ describe("stake-program", () => {
       .unstake(unstakeAmount)
       .accounts({
         staker: staker.publicKey,
-        mint: usdcMintKp.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
         stakeInfo,
-        vaultTokenAccount,
+        positionTokenAccount: null,
+        stakeVault,
         rewardVault,
         stakerTokenAccount,
+        stakerRewardAccount,
         systemProgram: anchor.web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       } as any)
       .signers([staker])
@@ -229,18 +418,431 @@ describe("stake-program", () => {
       provider.connection,
       stakerTokenAccount
     );
+    const stakerRewardAccountInfo = await getAccount(
+      provider.connection,
+      stakerRewardAccount
+    );
     const rewardVaultAccount = await getAccount(
       provider.connection,
       rewardVault
     );
     const vaultAccount = await getAccount(
       provider.connection,
-      vaultTokenAccount
+      stakeVault
     );
 
-    // Optional: verify reward logic more precisely here
-    expect(Number(stakerAccount.amount)).to.be.greaterThan(1000 * 10 ** 6);
+    // Principal comes back in the stake mint, rewards in the reward mint
+    expect(Number(stakerAccount.amount)).to.equal(1000 * 10 ** 6);
+    expect(Number(stakerRewardAccountInfo.amount)).to.be.greaterThan(0);
     expect(Number(vaultAccount.amount)).to.equal(0);
     expect(Number(rewardVaultAccount.amount)).to.be.lessThan(1000 * 10 ** 6);
   });
+
+  it("Emergency withdraw only works while paused, even when locked", async () => {
+    const stakeAccounts = {
+      staker: staker.publicKey,
+      stakeMint: stakeMintKp.publicKey,
+      rewardMint: rewardMintKp.publicKey,
+      config,
+      stakeInfo,
+      positionTokenAccount: null,
+      stakeVault,
+      stakerTokenAccount,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      stakeTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+
+    // Khóa 30 ngày
+    await program.methods
+      .stake(new BN(100 * 10 ** 6), 30, new BN(0))
+      .accounts(stakeAccounts as any)
+      .signers([staker])
+      .rpc();
+
+    // Pool đang chạy: không được lách thời gian khóa bằng rút khẩn cấp
+    try {
+      await program.methods
+        .emergencyWithdraw()
+        .accounts(stakeAccounts as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected emergencyWithdraw to fail");
+    } catch (e) {
+      expect(e.message).to.include("NotPaused");
+    }
+    const locked = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(locked.amount.toNumber()).to.equal(100 * 10 ** 6);
+    expect(locked.lockUntil.toNumber()).to.be.greaterThan(Date.now() / 1000);
+
+    await program.methods
+      .setPaused(true)
+      .accounts({ admin: provider.publicKey, config } as any)
+      .rpc();
+
+    // Pool tạm dừng: không stake thêm được
+    try {
+      await program.methods
+        .stake(new BN(1), 0, new BN(0))
+        .accounts(stakeAccounts as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected stake to fail");
+    } catch (e) {
+      expect(e.message).to.include("Paused");
+    }
+
+    await program.methods
+      .emergencyWithdraw()
+      .accounts(stakeAccounts as any)
+      .signers([staker])
+      .rpc();
+
+    const stakerAccount = await getAccount(
+      provider.connection,
+      stakerTokenAccount
+    );
+    expect(Number(stakerAccount.amount)).to.equal(1000 * 10 ** 6);
+
+    const configAccount = await program.account.stakeConfig.fetch(config);
+    expect(configAccount.totalLiabilities.toNumber()).to.equal(0);
+    expect(await provider.connection.getAccountInfo(stakeInfo)).to.be.null;
+
+    await program.methods
+      .setPaused(false)
+      .accounts({ admin: provider.publicKey, config } as any)
+      .rpc();
+  });
+
+  it("Request unstake and withdraw after unbonding", async () => {
+    const ticket = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("unbonding"),
+        stakeInfo.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    await program.methods
+      .stake(new BN(100 * 10 ** 6), 0, new BN(0))
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount: null,
+        stakeVault,
+        stakerTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    // Khi pool có thời gian chờ, rút ngay bị từ chối
+    await program.methods
+      .updateConfig(new BN(100), new BN(1), new BN(1_000_000), new BN(0), new BN(60))
+      .accounts({ admin: provider.publicKey, config } as any)
+      .rpc();
+    try {
+      await program.methods
+        .unstake(new BN(100 * 10 ** 6))
+        .accounts({
+          staker: staker.publicKey,
+          stakeMint: stakeMintKp.publicKey,
+          rewardMint: rewardMintKp.publicKey,
+          config,
+          stakeInfo,
+          positionTokenAccount: null,
+          stakeVault,
+          rewardVault,
+          stakerTokenAccount,
+          stakerRewardAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          stakeTokenProgram: TOKEN_PROGRAM_ID,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected unstake to fail");
+    } catch (e) {
+      expect(e.message).to.include("UnbondingRequired");
+    }
+    await program.methods
+      .updateConfig(new BN(100), new BN(1), new BN(1_000_000), new BN(0), new BN(0))
+      .accounts({ admin: provider.publicKey, config } as any)
+      .rpc();
+
+    await program.methods
+      .requestUnstake(new BN(100 * 10 ** 6))
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount: null,
+        ticket,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    const unbonding = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(unbonding.isStaked).to.be.false;
+    expect(unbonding.unbondingAmount.toNumber()).to.equal(100 * 10 ** 6);
+
+    // Pool này có unbonding_period = 0 nên rút được ngay
+    await program.methods
+      .withdrawUnbonded()
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        stakeInfo,
+        positionTokenAccount: null,
+        ticket,
+        stakeVault,
+        stakerTokenAccount,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    const stakerAccount = await getAccount(
+      provider.connection,
+      stakerTokenAccount
+    );
+    expect(Number(stakerAccount.amount)).to.equal(1000 * 10 ** 6);
+    expect(await provider.connection.getAccountInfo(ticket)).to.be.null;
+  });
+
+  it("Position NFT moves control to its holder", async () => {
+    const positionMint = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), stakeInfo.toBuffer()],
+      program.programId
+    )[0];
+    const positionTokenAccount = getAssociatedTokenAddressSync(
+      positionMint,
+      staker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .stake(new BN(100 * 10 ** 6), 0, new BN(0))
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount: null,
+        stakeVault,
+        stakerTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    // Chuyển SOL vào địa chỉ mint trước không chặn được việc phát hành NFT
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: positionMint,
+          lamports: 1_000_000,
+        })
+      )
+    );
+
+    await program.methods
+      .createPositionNft()
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionMint,
+        positionTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    const mint = await getMint(
+      provider.connection,
+      positionMint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(mint.supply)).to.equal(1);
+    expect(mint.mintAuthority).to.be.null;
+
+    const stakeInfoAccount = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(stakeInfoAccount.positionMint.toBase58()).to.equal(
+      positionMint.toBase58()
+    );
+
+    const claimAccounts = {
+      staker: staker.publicKey,
+      stakeMint: stakeMintKp.publicKey,
+      rewardMint: rewardMintKp.publicKey,
+      config,
+      stakeInfo,
+      rewardVault,
+      stakerRewardAccount,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+
+    // Không xuất trình NFT thì không còn quyền với vị thế
+    try {
+      await program.methods
+        .claim()
+        .accounts({ ...claimAccounts, positionTokenAccount: null } as any)
+        .signers([staker])
+        .rpc();
+      throw new Error("Expected claim to fail");
+    } catch (e) {
+      expect(e.message).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .claim()
+      .accounts({ ...claimAccounts, positionTokenAccount } as any)
+      .signers([staker])
+      .rpc();
+
+    const afterClaim = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(afterClaim.pendingReward.toNumber()).to.equal(0);
+
+    // Người giữ NFT nạp thêm qua top_up, vị thế tìm theo địa chỉ
+    await program.methods
+      .topUp(new BN(50 * 10 ** 6), 0)
+      .accounts({
+        holder: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount,
+        stakeVault,
+        holderTokenAccount: stakerTokenAccount,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+    const toppedUp = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(toppedUp.amount.toNumber()).to.equal(150 * 10 ** 6);
+
+    await program.methods
+      .unstake(toppedUp.amount)
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        stakeInfo,
+        positionTokenAccount,
+        stakeVault,
+        rewardVault,
+        stakerTokenAccount,
+        stakerRewardAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    // Vị thế rỗng nhưng còn NFT nên chưa bị đóng; close_position đốt NFT và hoàn rent
+    const emptied = await program.account.stakeInfo.fetch(stakeInfo);
+    expect(emptied.amount.toNumber()).to.equal(0);
+    expect(emptied.pendingReward.toNumber()).to.equal(0);
+
+    await program.methods
+      .closePosition()
+      .accounts({
+        holder: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        stakeInfo,
+        positionMint,
+        positionTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(stakeInfo)).to.be.null;
+    expect(await provider.connection.getAccountInfo(positionMint)).to.be.null;
+    expect(
+      await provider.connection.getAccountInfo(positionTokenAccount)
+    ).to.be.null;
+  });
+
+  it("Merges two positions of the same staker", async () => {
+    const first = positionPda(1);
+    const second = positionPda(2);
+    const stakeAccounts = (stakeInfo: anchor.web3.PublicKey) => ({
+      staker: staker.publicKey,
+      stakeMint: stakeMintKp.publicKey,
+      rewardMint: rewardMintKp.publicKey,
+      config,
+      stakeInfo,
+      positionTokenAccount: null,
+      stakeVault,
+      stakerTokenAccount,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      stakeTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    });
+
+    await program.methods
+      .stake(new BN(10 * 10 ** 6), 0, new BN(1))
+      .accounts(stakeAccounts(first) as any)
+      .signers([staker])
+      .rpc();
+    await program.methods
+      .stake(new BN(20 * 10 ** 6), 0, new BN(2))
+      .accounts(stakeAccounts(second) as any)
+      .signers([staker])
+      .rpc();
+
+    const secondAccount = await program.account.stakeInfo.fetch(second);
+    expect(secondAccount.positionIndex.toNumber()).to.equal(2);
+
+    await program.methods
+      .mergePositions()
+      .accounts({
+        staker: staker.publicKey,
+        stakeMint: stakeMintKp.publicKey,
+        rewardMint: rewardMintKp.publicKey,
+        config,
+        source: second,
+        destination: first,
+        positionTokenAccount: null,
+        sourcePositionTokenAccount: null,
+      } as any)
+      .signers([staker])
+      .rpc();
+
+    const merged = await program.account.stakeInfo.fetch(first);
+    expect(merged.amount.toNumber()).to.equal(30 * 10 ** 6);
+    expect(merged.multiplierBps.toNumber()).to.equal(10_000);
+    expect(await provider.connection.getAccountInfo(second)).to.be.null;
+  });
 });
@@ -9,6 +9,9 @@ pub const POOL_SEED: &[u8] = b"pool";
 #[constant]
 pub const STAKEINFO_SEED: &[u8] = b"stakeinfo";
 
+// scale of pool.acc_reward_per_share to keep precision when total_staked is large
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
 #[constant]
 pub const MINT_URI: &str = "https://raw.githubusercontent.com/HongThaiPham/solana-bootcamp-autumn-2024/main/week-4/token-2022-staking/app/assets/token-info.json";
 
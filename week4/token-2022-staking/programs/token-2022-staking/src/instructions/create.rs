@@ -66,7 +66,11 @@ impl<'info> CreatePool<'info> {
             reward_mint: self.reward_mint.to_account_info().key(), 
             reward_ata: self.reward_ata.to_account_info().key() , 
             allocation ,
-            reward_per_slot: reward_per_second
+            reward_per_slot: reward_per_second,
+            total_staked: 0,
+            acc_reward_per_share: 0,
+            last_reward_slot: Clock::get()?.slot,
+            distributed: 0,
         });
         self.mint_reward(allocation, bumps)?;
         Ok(())
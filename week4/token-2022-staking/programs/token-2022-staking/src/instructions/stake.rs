@@ -13,6 +13,7 @@ pub struct Stake<'info> {
     // check pool account valid
     // use has_one to check if pool account has a field stake_mint = stake_mint account, if not, return InvalidStakeMintAccount error
     #[account(
+      mut,
       has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,
     )]
     pub pool: Account<'info, Pool>,
@@ -62,21 +63,16 @@ impl<'info> Stake<'info> {
     }
 
     fn update_stake_info(&mut self, amount: u64) -> Result<()> {
-      // check if the staker has staked before
-      // if staker has staked before, calculate the reward and add the amount to the stake_info account
-      // then update the stake_info account
-      if self.stake_info.amount.gt(&0) {
-        self.stake_info.reward = self.pool.calculate_reward(self.stake_info.last_deposit_slot, Clock::get()?.slot);
-        self.stake_info.amount = self.stake_info.amount.checked_add(amount).ok_or(MyErrorCode::Overflow)?;
-      } else {
-        // else, set the amount and reward to the stake_info account
-        self.stake_info.amount = amount;
-        self.stake_info.reward = 0;
-      }
+      // accrue the pool reward up to the current slot before total_staked changes
+      self.pool.update(Clock::get()?.slot)?;
+
+      // keep the reward earned by the old amount (zero for a new stake_info),
+      // the added amount only earns from the current slot
+      let new_amount = self.stake_info.amount.checked_add(amount).ok_or(MyErrorCode::Overflow)?;
+      self.stake_info.update_amount(&self.pool, new_amount)?;
+      self.pool.total_staked = self.pool.total_staked.checked_add(amount).ok_or(MyErrorCode::Overflow)?;
 
       self.stake_info.staker = self.signer.to_account_info().key();
-      // udate the last_deposit_slot to the current slot to keep track reward
-      self.stake_info.last_deposit_slot = Clock::get()?.slot;
       Ok(())
     }
 
@@ -9,6 +9,7 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
     #[account(
+      mut,
       seeds = [POOL_SEED, stake_mint.key().as_ref()],
       bump,
       has_one = stake_mint @MyErrorCode::InvalidStakeMintAccount,  
@@ -71,12 +72,14 @@ impl<'info> Unstake<'info> {
   pub fn handler(&mut self, amount: u64, bumps: &UnstakeBumps) -> Result<()> {
     require_gt!(amount, 0, MyErrorCode::InvalidUnstakeAmount);
     require_gte!(self.stake_info.amount, amount, MyErrorCode::InsufficientStakeAmount );
+    // update the stake info
+    self.update_stake_info(amount)?;
     // return the staked amount to the staker
     self.back_to_staker(amount, bumps)?;
     // return the reward to the staker
     self.reward_to_staker(bumps)?;
-    // update the stake info
-    self.update_stake_info(amount,bumps)?;
+    // close the stake info if the staker unstake all tokens
+    self.close_stake_info(bumps)?;
     Ok(())
   }
   
@@ -84,7 +87,11 @@ impl<'info> Unstake<'info> {
   fn reward_to_staker(&mut self, bumps: &UnstakeBumps) -> Result<()> {
     let seeds = &[POOL_SEED, self.stake_mint.to_account_info().key.as_ref(), &[bumps.pool]];
     let signer_seeds = &[&seeds[..]];
-    let reward_amount = self.stake_info.reward.checked_add(self.pool.calculate_reward(self.stake_info.last_deposit_slot , Clock::get()?.slot)).ok_or(MyErrorCode::Overflow)?; 
+    let reward_amount = self.stake_info.reward;
+    if reward_amount.eq(&0) {
+      return Ok(());
+    }
+    self.stake_info.reward = 0;
 
     transfer_checked(
       CpiContext::new_with_signer(
@@ -104,17 +111,22 @@ impl<'info> Unstake<'info> {
     Ok(())
   }
 
-  fn update_stake_info(&mut self, amount: u64, bumps: &UnstakeBumps) -> Result<()> {
-    //calculate the new stake amount
+  fn update_stake_info(&mut self, amount: u64) -> Result<()> {
+    // accrue the pool reward up to the current slot before total_staked changes
+    self.pool.update(Clock::get()?.slot)?;
+
+    //calculate the new stake amount, the reward earned so far is kept in stake_info.reward
     let new_amount = self.stake_info.amount.checked_sub(amount).ok_or(MyErrorCode::Overflow)?;
-    self.stake_info.amount = new_amount;
-    self.stake_info.reward = 0;
-    self.stake_info.last_deposit_slot = Clock::get()?.slot;
+    self.stake_info.update_amount(&self.pool, new_amount)?;
+    self.pool.total_staked = self.pool.total_staked.checked_sub(amount).ok_or(MyErrorCode::Overflow)?;
+    Ok(())
+  }
 
+  fn close_stake_info(&mut self, bumps: &UnstakeBumps) -> Result<()> {
     // check if staker unstake all tokens
     // close the stake_info_ata account and stake_info account
     // return rent fee to the staker
-    if new_amount.eq(&0) {
+    if self.stake_info.amount.eq(&0) {
       let seeds = &[STAKEINFO_SEED, self.pool.to_account_info().key.as_ref(), self.signer.to_account_info().key.as_ref(),&[bumps.stake_info]];
       let signer_seeds = &[&seeds[..]];
 
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::MyErrorCode, ACC_REWARD_PRECISION};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Pool {
@@ -9,11 +11,125 @@ pub struct Pool {
     pub reward_ata: Pubkey,
     pub allocation: u64,
     pub reward_per_slot: u64,
+    // total amount staked by all stakers, the emission of each slot is shared pro-rata on it
+    pub total_staked: u64,
+    // reward accumulated per staked token since the pool was created, scaled by ACC_REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+    pub last_reward_slot: u64,
+    // reward already emitted to stakers, never exceeds allocation
+    pub distributed: u64,
 }
 
 impl Pool {
-    pub fn calculate_reward(&self, from_slot: u64, to_slot: u64) -> u64 {
-        let elapsed = to_slot - from_slot;
-        elapsed * self.reward_per_slot
+    // accrue the emission since last_reward_slot into acc_reward_per_share
+    // the emission stops once the whole allocation has been distributed
+    pub fn update(&mut self, current_slot: u64) -> Result<()> {
+        if current_slot <= self.last_reward_slot {
+            return Ok(());
+        }
+        // nobody is staking, the emission of these slots is not distributed
+        if self.total_staked == 0 {
+            self.last_reward_slot = current_slot;
+            return Ok(());
+        }
+
+        let elapsed = current_slot - self.last_reward_slot;
+        let remaining = self.allocation.saturating_sub(self.distributed);
+        let reward = elapsed.saturating_mul(self.reward_per_slot).min(remaining);
+
+        let increment = (reward as u128)
+            .checked_mul(ACC_REWARD_PRECISION)
+            .ok_or(MyErrorCode::Overflow)?
+            / self.total_staked as u128;
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(MyErrorCode::Overflow)?;
+        self.distributed = self.distributed.checked_add(reward).ok_or(MyErrorCode::Overflow)?;
+        self.last_reward_slot = current_slot;
+        Ok(())
+    }
+
+    // reward earned by `amount` staked tokens since the beginning of the pool
+    // rounded down, so the sum of all payouts stays within distributed
+    pub fn accumulated_reward(&self, amount: u64) -> Result<u128> {
+        Ok((amount as u128)
+            .checked_mul(self.acc_reward_per_share)
+            .ok_or(MyErrorCode::Overflow)?
+            / ACC_REWARD_PRECISION)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn pool(allocation: u64, reward_per_slot: u64) -> Pool {
+        Pool {
+            authority: Pubkey::default(),
+            stake_mint: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            reward_ata: Pubkey::default(),
+            allocation,
+            reward_per_slot,
+            total_staked: 0,
+            acc_reward_per_share: 0,
+            last_reward_slot: 0,
+            distributed: 0,
+        }
+    }
+
+    #[test]
+    fn update_without_stakers_skips_the_emission() {
+        let mut pool = pool(1_000, 10);
+        pool.update(5).unwrap();
+
+        assert_eq!(pool.last_reward_slot, 5);
+        assert_eq!(pool.acc_reward_per_share, 0);
+        assert_eq!(pool.distributed, 0);
+    }
+
+    #[test]
+    fn update_spreads_the_emission_over_total_staked() {
+        let mut pool = pool(1_000, 10);
+        pool.total_staked = 4;
+        pool.update(3).unwrap();
+
+        // 3 slots * 10 per slot shared by 4 staked tokens
+        assert_eq!(pool.distributed, 30);
+        assert_eq!(pool.acc_reward_per_share, 30 * ACC_REWARD_PRECISION / 4);
+        assert_eq!(pool.accumulated_reward(4).unwrap(), 30);
+    }
+
+    #[test]
+    fn update_ignores_slots_already_accrued() {
+        let mut pool = pool(1_000, 10);
+        pool.total_staked = 1;
+        pool.update(5).unwrap();
+        let acc = pool.acc_reward_per_share;
+
+        pool.update(5).unwrap();
+        pool.update(4).unwrap();
+        assert_eq!(pool.acc_reward_per_share, acc);
+        assert_eq!(pool.last_reward_slot, 5);
+        assert_eq!(pool.distributed, 50);
+    }
+
+    #[test]
+    fn update_stops_at_the_allocation() {
+        let mut pool = pool(1_000, 10);
+        pool.total_staked = 3;
+
+        // allocation / reward_per_slot = 100 slots, run well past them in uneven steps
+        pool.update(70).unwrap();
+        pool.update(130).unwrap();
+        assert_eq!(pool.distributed, pool.allocation);
+        let acc = pool.acc_reward_per_share;
+
+        pool.update(1_000).unwrap();
+        assert_eq!(pool.distributed, pool.allocation);
+        assert_eq!(pool.acc_reward_per_share, acc);
+        // rounding down keeps the stakers' share within the allocation
+        assert!(pool.accumulated_reward(3).unwrap() <= pool.allocation as u128);
     }
 }
@@ -1,10 +1,123 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::MyErrorCode, Pool};
+
 #[account]
 #[derive(InitSpace)]
 pub struct StakeInfo {
     pub staker: Pubkey,
     pub amount: u64,
-    pub last_deposit_slot: u64,
+    // part of pool.accumulated_reward(amount) that is not owed to the staker
+    pub reward_debt: u128,
+    // reward earned but not paid yet
     pub reward: u64,
 }
+
+impl StakeInfo {
+    // reward earned since the last update of reward_debt, pool must be updated first
+    pub fn pending_reward(&self, pool: &Pool) -> Result<u64> {
+        let pending = pool
+            .accumulated_reward(self.amount)?
+            .checked_sub(self.reward_debt)
+            .ok_or(MyErrorCode::Overflow)?;
+        u64::try_from(pending).map_err(|_| MyErrorCode::Overflow.into())
+    }
+
+    // move the pending reward of the old amount to reward, then change the amount
+    // and reset reward_debt so the new amount only earns from now on
+    pub fn update_amount(&mut self, pool: &Pool, amount: u64) -> Result<()> {
+        let pending = self.pending_reward(pool)?;
+        self.reward = self.reward.checked_add(pending).ok_or(MyErrorCode::Overflow)?;
+        self.amount = amount;
+        self.reward_debt = pool.accumulated_reward(amount)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::pool::tests::pool;
+
+    fn stake_info() -> StakeInfo {
+        StakeInfo {
+            staker: Pubkey::default(),
+            amount: 0,
+            reward_debt: 0,
+            reward: 0,
+        }
+    }
+
+    // stake `amount` more at `slot`, the same way the stake instruction does
+    fn stake(pool: &mut Pool, info: &mut StakeInfo, amount: u64, slot: u64) {
+        pool.update(slot).unwrap();
+        info.update_amount(pool, info.amount + amount).unwrap();
+        pool.total_staked += amount;
+    }
+
+    #[test]
+    fn update_amount_keeps_the_reward_of_the_old_amount() {
+        let mut pool = pool(1_000_000, 100);
+        let mut info = stake_info();
+        stake(&mut pool, &mut info, 10, 0);
+
+        pool.update(5).unwrap();
+        info.update_amount(&pool, 20).unwrap();
+        pool.total_staked = 20;
+        assert_eq!(info.reward, 500);
+        assert_eq!(info.pending_reward(&pool).unwrap(), 0);
+
+        // the new amount only earns from slot 5
+        pool.update(10).unwrap();
+        assert_eq!(info.pending_reward(&pool).unwrap(), 500);
+    }
+
+    #[test]
+    fn new_stake_earns_nothing_from_before_it_joined() {
+        let mut pool = pool(1_000_000, 100);
+        let mut first = stake_info();
+        let mut second = stake_info();
+        stake(&mut pool, &mut first, 10, 0);
+        stake(&mut pool, &mut second, 10, 5);
+
+        assert_eq!(second.reward, 0);
+        assert_eq!(second.pending_reward(&pool).unwrap(), 0);
+        assert_eq!(first.pending_reward(&pool).unwrap(), 500);
+    }
+
+    #[test]
+    fn two_stakers_split_the_emission_pro_rata() {
+        let mut pool = pool(1_000_000, 100);
+        let mut small = stake_info();
+        let mut large = stake_info();
+        stake(&mut pool, &mut small, 1_000, 0);
+        stake(&mut pool, &mut large, 3_000, 0);
+
+        pool.update(8).unwrap();
+        assert_eq!(small.pending_reward(&pool).unwrap(), 200);
+        assert_eq!(large.pending_reward(&pool).unwrap(), 600);
+
+        // unstaking settles the reward into stake_info.reward
+        small.update_amount(&pool, 0).unwrap();
+        assert_eq!(small.reward, 200);
+        assert_eq!(small.reward_debt, 0);
+    }
+
+    #[test]
+    fn payouts_never_exceed_the_distributed_reward() {
+        let mut pool = pool(1_000, 7);
+        let mut infos = [stake_info(), stake_info(), stake_info()];
+        for (slot, (info, amount)) in infos.iter_mut().zip([1, 2, 4]).enumerate() {
+            stake(&mut pool, info, amount, slot as u64);
+        }
+
+        pool.update(1_000).unwrap();
+        let mut paid = 0;
+        for info in infos.iter_mut() {
+            info.update_amount(&pool, 0).unwrap();
+            paid += info.reward;
+        }
+        assert_eq!(pool.distributed, pool.allocation);
+        assert!(paid <= pool.distributed);
+    }
+}
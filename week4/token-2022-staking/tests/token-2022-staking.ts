@@ -44,14 +44,6 @@ describe("token-2022-staking", () => {
   // create a new mint for stake token
   const stakeMintKeypair = anchor.web3.Keypair.generate();
 
-  const metadata: TokenMetadata = {
-    mint: stakeMintKeypair.publicKey,
-    name: "STAKE TOKEN",
-    symbol: "BCST",
-    uri: "https://raw.githubusercontent.com/HongThaiPham/solana-bootcamp-autumn-2024/main/week-4/token-2022-staking/app/assets/token-info.json",
-    additionalMetadata: [],
-  };
-
   // create staker account
   const staker = anchor.web3.Keypair.generate();
   console.log("Staker address: ", staker.publicKey.toBase58());
@@ -95,6 +87,182 @@ describe("token-2022-staking", () => {
 
   const stakeAmount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
   const unstakeAmount = new anchor.BN(5 * anchor.web3.LAMPORTS_PER_SOL);
+
+  const tokenAccount = (
+    mint: anchor.web3.PublicKey,
+    owner: anchor.web3.PublicKey
+  ) => getAssociatedTokenAddressSync(mint, owner, false, TOKEN_2022_PROGRAM_ID);
+
+  const tokenBalance = async (address: anchor.web3.PublicKey) =>
+    new anchor.BN(
+      (
+        await getAccount(
+          provider.connection,
+          address,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount.toString()
+    );
+
+  const fundedKeypair = async () => {
+    const keypair = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(
+      keypair.publicKey,
+      10 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction({
+      signature: signature,
+      ...(await provider.connection.getLatestBlockhash()),
+    });
+    return keypair;
+  };
+
+  // create a token-2022 stake mint and give each holder 1000 tokens
+  const createStakeMint = async (
+    mintKeypair: anchor.web3.Keypair,
+    holders: anchor.web3.PublicKey[]
+  ) => {
+    const metadata: TokenMetadata = {
+      mint: mintKeypair.publicKey,
+      name: "STAKE TOKEN",
+      symbol: "BCST",
+      uri: "https://raw.githubusercontent.com/HongThaiPham/solana-bootcamp-autumn-2024/main/week-4/token-2022-staking/app/assets/token-info.json",
+      additionalMetadata: [],
+    };
+    const mintLen = getMintLen([ExtensionType.MetadataPointer]);
+
+    const metadataLen = TYPE_SIZE + LENGTH_SIZE + pack(metadata).length;
+
+    const mintLamports =
+      await provider.connection.getMinimumBalanceForRentExemption(
+        mintLen + metadataLen
+      );
+
+    const mintTransaction = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: provider.publicKey,
+        newAccountPubkey: mintKeypair.publicKey,
+        space: mintLen,
+        lamports: mintLamports,
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeMetadataPointerInstruction(
+        mintKeypair.publicKey,
+        provider.publicKey,
+        mintKeypair.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(
+        mintKeypair.publicKey,
+        9,
+        provider.publicKey,
+        null,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeInstruction({
+        programId: TOKEN_2022_PROGRAM_ID,
+        mint: mintKeypair.publicKey,
+        metadata: mintKeypair.publicKey,
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        mintAuthority: provider.publicKey,
+        updateAuthority: provider.publicKey,
+      })
+    );
+    for (const holder of holders) {
+      const holderTokenAccount = tokenAccount(mintKeypair.publicKey, holder);
+      mintTransaction.add(
+        createAssociatedTokenAccountInstruction(
+          provider.publicKey,
+          holderTokenAccount,
+          holder,
+          mintKeypair.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createMintToCheckedInstruction(
+          mintKeypair.publicKey,
+          holderTokenAccount,
+          provider.publicKey,
+          1_000 * anchor.web3.LAMPORTS_PER_SOL,
+          9,
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+    }
+    return provider.sendAndConfirm(mintTransaction, [mintKeypair]);
+  };
+
+  const createPool = (
+    stakeMint: anchor.web3.PublicKey,
+    allocation: anchor.BN,
+    perSlot: anchor.BN
+  ) =>
+    program.methods
+      .createPool(allocation, perSlot)
+      .accounts({
+        signer: provider.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        rewardMint: rewardMintKeypair.publicKey,
+        stakeMint,
+      })
+      .rpc();
+
+  const poolAddress = (stakeMint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), stakeMint.toBuffer()],
+      program.programId
+    )[0];
+
+  const stakeIx = (
+    stakeMint: anchor.web3.PublicKey,
+    signer: anchor.web3.Keypair,
+    amount: anchor.BN
+  ) =>
+    program.methods
+      .stake(amount)
+      .accounts({
+        signer: signer.publicKey,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        pool: poolAddress(stakeMint),
+      })
+      .instruction();
+
+  const unstakeIx = (
+    stakeMint: anchor.web3.PublicKey,
+    signer: anchor.web3.Keypair,
+    amount: anchor.BN
+  ) =>
+    program.methods
+      .unstake(amount)
+      .accountsPartial({
+        rewardMint: rewardMintKeypair.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        signer: signer.publicKey,
+        stakeMint,
+      })
+      .instruction();
+
+  // one transaction for several stakers, so they all land in the same slot
+  const sendTogether = async (
+    ixs: Promise<anchor.web3.TransactionInstruction>[],
+    signers: anchor.web3.Keypair[]
+  ) =>
+    provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(...(await Promise.all(ixs))),
+      signers
+    );
+
+  const waitForSlot = async (slot: number) => {
+    while ((await provider.connection.getSlot("confirmed")) < slot) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+  };
+
   before("Prepare test", async () => {
     // faucet to staker
     {
@@ -110,67 +278,7 @@ describe("token-2022-staking", () => {
 
     {
       // create stake mint token
-      const mintLen = getMintLen([ExtensionType.MetadataPointer]);
-
-      const metadataLen = TYPE_SIZE + LENGTH_SIZE + pack(metadata).length;
-
-      const mintLamports =
-        await provider.connection.getMinimumBalanceForRentExemption(
-          mintLen + metadataLen
-        );
-
-      const mintTransaction = new anchor.web3.Transaction().add(
-        anchor.web3.SystemProgram.createAccount({
-          fromPubkey: staker.publicKey,
-          newAccountPubkey: stakeMintKeypair.publicKey,
-          space: mintLen,
-          lamports: mintLamports,
-          programId: TOKEN_2022_PROGRAM_ID,
-        }),
-        createInitializeMetadataPointerInstruction(
-          stakeMintKeypair.publicKey,
-          staker.publicKey,
-          stakeMintKeypair.publicKey,
-          TOKEN_2022_PROGRAM_ID
-        ),
-        createInitializeMintInstruction(
-          stakeMintKeypair.publicKey,
-          9,
-          staker.publicKey,
-          null,
-          TOKEN_2022_PROGRAM_ID
-        ),
-        createInitializeInstruction({
-          programId: TOKEN_2022_PROGRAM_ID,
-          mint: stakeMintKeypair.publicKey,
-          metadata: stakeMintKeypair.publicKey,
-          name: metadata.name,
-          symbol: metadata.symbol,
-          uri: metadata.uri,
-          mintAuthority: staker.publicKey,
-          updateAuthority: staker.publicKey,
-        }),
-        createAssociatedTokenAccountInstruction(
-          staker.publicKey,
-          stakerTokenAccount,
-          staker.publicKey,
-          stakeMintKeypair.publicKey,
-          TOKEN_2022_PROGRAM_ID
-        ),
-        createMintToCheckedInstruction(
-          stakeMintKeypair.publicKey,
-          stakerTokenAccount,
-          staker.publicKey,
-          1_000 * anchor.web3.LAMPORTS_PER_SOL,
-          9,
-          [],
-          TOKEN_2022_PROGRAM_ID
-        )
-      );
-      const tx = await provider.sendAndConfirm(mintTransaction, [
-        stakeMintKeypair,
-        staker,
-      ]);
+      const tx = await createStakeMint(stakeMintKeypair, [staker.publicKey]);
 
       console.log("Prepare transaction signature: ", tx);
     }
@@ -196,19 +304,11 @@ describe("token-2022-staking", () => {
   });
 
   it("Should create a new pool", async () => {
-    const tx = await program.methods
-      .createPool(
-        new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
-        rewardPerSlot
-      )
-      .accounts({
-        signer: provider.publicKey,
-        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
-        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
-        rewardMint: rewardMintKeypair.publicKey,
-        stakeMint: stakeMintKeypair.publicKey,
-      })
-      .rpc();
+    const tx = await createPool(
+      stakeMintKeypair.publicKey,
+      new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
+      rewardPerSlot
+    );
     console.log("Your transaction signature", tx);
 
     const poolAccount = await program.account.pool.fetch(pool);
@@ -220,6 +320,8 @@ describe("token-2022-staking", () => {
         new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL)
       )
     );
+    assert(poolAccount.totalStaked.eq(new anchor.BN(0)));
+    assert(poolAccount.distributed.eq(new anchor.BN(0)));
 
     const rewardAtaAccount = await getAccount(
       provider.connection,
@@ -253,6 +355,9 @@ describe("token-2022-staking", () => {
     assert(stakeInfoAccount.amount.eq(stakeAmount));
     assert(stakeInfoAccount.reward.eq(new anchor.BN(0)));
 
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert(poolAccount.totalStaked.eq(stakeAmount));
+
     const stakeInfoAtaAccount = await getAccount(
       provider.connection,
       stakeInfoAta,
@@ -354,5 +459,103 @@ describe("token-2022-staking", () => {
         new anchor.BN(0)
       )
     );
+
+    // the only staker received the whole emission, capped at the allocation
+    const poolAccount = await program.account.pool.fetch(pool);
+    assert(poolAccount.totalStaked.eq(new anchor.BN(0)));
+    assert(poolAccount.distributed.lte(poolAccount.allocation));
+    assert(
+      new anchor.BN(stakerRewardAtaAccount.amount.toString()).lte(
+        poolAccount.distributed
+      )
+    );
+  });
+
+  it("Should split the reward between stakers pro-rata", async () => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const small = await fundedKeypair();
+    const large = await fundedKeypair();
+    await createStakeMint(mintKeypair, [small.publicKey, large.publicKey]);
+    const mint = mintKeypair.publicKey;
+    await createPool(
+      mint,
+      new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
+      rewardPerSlot
+    );
+
+    const smallAmount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+    const largeAmount = new anchor.BN(30 * anchor.web3.LAMPORTS_PER_SOL);
+    await sendTogether(
+      [stakeIx(mint, small, smallAmount), stakeIx(mint, large, largeAmount)],
+      [small, large]
+    );
+
+    const stakedAt = (await program.account.pool.fetch(poolAddress(mint)))
+      .lastRewardSlot;
+    await waitForSlot(stakedAt.toNumber() + 3);
+
+    await sendTogether(
+      [
+        unstakeIx(mint, small, smallAmount),
+        unstakeIx(mint, large, largeAmount),
+      ],
+      [small, large]
+    );
+
+    const poolAccount = await program.account.pool.fetch(poolAddress(mint));
+    const precision = new anchor.BN(1_000_000_000_000);
+    const expected = (amount: anchor.BN) =>
+      amount.mul(poolAccount.accRewardPerShare).div(precision);
+
+    const smallReward = await tokenBalance(
+      tokenAccount(rewardMintKeypair.publicKey, small.publicKey)
+    );
+    const largeReward = await tokenBalance(
+      tokenAccount(rewardMintKeypair.publicKey, large.publicKey)
+    );
+
+    // both staked and unstaked in the same slots, so the emission is split 1:3
+    assert(smallReward.gt(new anchor.BN(0)));
+    assert(smallReward.eq(expected(smallAmount)));
+    assert(largeReward.eq(expected(largeAmount)));
+    assert(largeReward.sub(smallReward.muln(3)).abs().lten(3));
+    assert(smallReward.add(largeReward).lte(poolAccount.distributed));
+    assert(poolAccount.totalStaked.eq(new anchor.BN(0)));
+  });
+
+  it("Should stop the emission at the allocation", async () => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const holder = await fundedKeypair();
+    await createStakeMint(mintKeypair, [holder.publicKey]);
+    const mint = mintKeypair.publicKey;
+    // the whole allocation is emitted after 3 slots
+    const allocation = rewardPerSlot.muln(3);
+    await createPool(mint, allocation, rewardPerSlot);
+
+    await sendTogether([stakeIx(mint, holder, stakeAmount)], [holder]);
+    const stakedAt = (await program.account.pool.fetch(poolAddress(mint)))
+      .lastRewardSlot;
+    // run well past allocation / reward_per_slot slots
+    await waitForSlot(stakedAt.toNumber() + 10);
+
+    await sendTogether([unstakeIx(mint, holder, stakeAmount)], [holder]);
+
+    const poolAccount = await program.account.pool.fetch(poolAddress(mint));
+    assert(poolAccount.distributed.eq(allocation));
+
+    // the only staker gets exactly the allocation and the pool has nothing left
+    const reward = await tokenBalance(
+      tokenAccount(rewardMintKeypair.publicKey, holder.publicKey)
+    );
+    assert(reward.eq(allocation));
+    const poolRewardBalance = await tokenBalance(
+      getAssociatedTokenAddressSync(
+        rewardMintKeypair.publicKey,
+        poolAddress(mint),
+        true,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    assert(poolRewardBalance.eq(new anchor.BN(0)));
   });
 });
@@ -0,0 +1,43 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum IndexerError {
+    /// A `Program data:` line of the voting program was not valid base64.
+    Base64(base64::DecodeError),
+    /// Event data matched a discriminator but did not deserialize.
+    Event(std::io::Error),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::Base64(err) => write!(f, "invalid event encoding: {err}"),
+            IndexerError::Event(err) => write!(f, "invalid event data: {err}"),
+            #[cfg(feature = "sqlite")]
+            IndexerError::Sqlite(err) => write!(f, "sqlite: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<base64::DecodeError> for IndexerError {
+    fn from(err: base64::DecodeError) -> Self {
+        IndexerError::Base64(err)
+    }
+}
+
+impl From<std::io::Error> for IndexerError {
+    fn from(err: std::io::Error) -> Self {
+        IndexerError::Event(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for IndexerError {
+    fn from(err: rusqlite::Error) -> Self {
+        IndexerError::Sqlite(err)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::IndexerError;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use voting::events::*;
+
+macro_rules! voting_events {
+    ($($name:ident),* $(,)?) => {
+        /// Every event the voting program emits.
+        pub enum VotingEvent {
+            $($name($name),)*
+        }
+
+        impl VotingEvent {
+            /// Decodes an event from its discriminator and Borsh body. Returns
+            /// `None` for data that is not one of the program's events.
+            pub fn decode(data: &[u8]) -> Result<Option<Self>, IndexerError> {
+                $(
+                    if let Some(mut body) = data.strip_prefix(<$name as Discriminator>::DISCRIMINATOR) {
+                        return Ok(Some(VotingEvent::$name($name::deserialize(&mut body)?)));
+                    }
+                )*
+                Ok(None)
+            }
+        }
+    };
+}
+
+voting_events!(
+    PollCreated,
+    QuestionAdded,
+    ConvictionEnabled,
+    CandidateAdded,
+    VoteCast,
+    VoteChanged,
+    VoteRetracted,
+    PollFinalized,
+    ProposalCreated,
+    ProposalExecuted,
+    TokensLocked,
+    LockMoved,
+    TokensUnlocked,
+    CandidateClosed,
+    ReceiptClosed,
+    ProposalClosed,
+    PollClosed,
+);
@@ -0,0 +1,14 @@
+//! Rebuilds a view of the voting program's polls from the events it emits,
+//! so dashboards can read tallies without fetching every candidate account.
+
+pub mod error;
+pub mod events;
+pub mod logs;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod view;
+
+pub use error::IndexerError;
+pub use events::VotingEvent;
+pub use logs::parse_logs;
+pub use view::{CandidateView, PollIndex, PollView};
@@ -0,0 +1,40 @@
+use crate::{IndexerError, VotingEvent};
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+const DATA_PREFIX: &str = "Program data: ";
+
+/// Extracts the events `program_id` emitted in one transaction, in order.
+///
+/// `Program data:` lines are attributed to the program on top of the invoke
+/// stack, so data logged by other programs (including ones the voting program
+/// calls) is ignored. A failed transaction has no effect on chain, so its
+/// events are dropped as well.
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Result<Vec<VotingEvent>, IndexerError> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = line.strip_prefix(DATA_PREFIX) {
+            if stack.last() == Some(&program_id.as_str()) {
+                if let Some(event) = VotingEvent::decode(&STANDARD.decode(data.trim())?)? {
+                    events.push(event);
+                }
+            }
+            continue;
+        }
+
+        let mut words = line.strip_prefix("Program ").unwrap_or_default().split_whitespace();
+        match (words.next(), words.next()) {
+            (Some(program), Some("invoke")) => stack.push(program),
+            (Some(_), Some("success")) => {
+                stack.pop();
+            }
+            (Some(_), Some(status)) if status.starts_with("failed") => return Ok(Vec::new()),
+            _ => {}
+        }
+    }
+    Ok(events)
+}
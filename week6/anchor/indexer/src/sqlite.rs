@@ -0,0 +1,133 @@
+use crate::{IndexerError, PollIndex};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use voting::state::{PollOutcome, VotingMode};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS polls (
+    poll_id INTEGER PRIMARY KEY,
+    creator TEXT NOT NULL,
+    description TEXT NOT NULL,
+    poll_start INTEGER NOT NULL,
+    poll_end INTEGER NOT NULL,
+    quorum INTEGER NOT NULL,
+    threshold_bps INTEGER NOT NULL,
+    total_votes INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    stake_mint TEXT NOT NULL,
+    open_receipts INTEGER NOT NULL,
+    has_proposal INTEGER NOT NULL,
+    proposal_executed INTEGER NOT NULL,
+    closed INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS questions (
+    poll_id INTEGER NOT NULL,
+    question_index INTEGER NOT NULL,
+    prompt TEXT NOT NULL,
+    PRIMARY KEY (poll_id, question_index)
+);
+CREATE TABLE IF NOT EXISTS candidates (
+    poll_id INTEGER NOT NULL,
+    question_index INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    votes INTEGER NOT NULL,
+    locked INTEGER NOT NULL,
+    winner INTEGER NOT NULL,
+    PRIMARY KEY (poll_id, question_index, name)
+);
+";
+
+/// SQLite copy of a `PollIndex`, for dashboards that query with SQL.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, IndexerError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Replaces the stored polls with the index's current state.
+    pub fn save(&mut self, index: &PollIndex) -> Result<(), IndexerError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM candidates", [])?;
+        tx.execute("DELETE FROM questions", [])?;
+        tx.execute("DELETE FROM polls", [])?;
+        {
+            let mut insert_poll = tx.prepare(
+                "INSERT INTO polls VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            )?;
+            let mut insert_question = tx.prepare(
+                "INSERT INTO questions VALUES (?1, ?2, ?3)",
+            )?;
+            let mut insert_candidate = tx.prepare(
+                "INSERT INTO candidates VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for poll in index.polls() {
+                insert_poll.execute(params![
+                    poll.poll_id,
+                    poll.creator.to_string(),
+                    poll.description,
+                    poll.poll_start,
+                    poll.poll_end,
+                    poll.quorum,
+                    poll.threshold_bps,
+                    poll.total_votes,
+                    outcome_name(poll.outcome),
+                    mode_name(poll.mode),
+                    poll.stake_mint.to_string(),
+                    poll.open_receipts,
+                    poll.has_proposal,
+                    poll.proposal_executed,
+                    poll.closed,
+                ])?;
+                for (question_index, prompt) in poll.questions.iter().enumerate() {
+                    insert_question.execute(params![poll.poll_id, question_index, prompt])?;
+                }
+                for ((question_index, name), candidate) in &poll.candidates {
+                    let winner = poll.winners.get(*question_index as usize) == Some(&Some(name.clone()));
+                    insert_candidate.execute(params![
+                        poll.poll_id,
+                        question_index,
+                        name,
+                        candidate.votes,
+                        candidate.locked,
+                        winner,
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn outcome_name(outcome: PollOutcome) -> &'static str {
+    match outcome {
+        PollOutcome::Pending => "pending",
+        PollOutcome::Passed => "passed",
+        PollOutcome::Failed => "failed",
+        PollOutcome::NoQuorum => "no_quorum",
+    }
+}
+
+fn mode_name(mode: VotingMode) -> &'static str {
+    match mode {
+        VotingMode::Standard => "standard",
+        VotingMode::Conviction => "conviction",
+    }
+}
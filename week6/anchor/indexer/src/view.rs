@@ -0,0 +1,210 @@
+use crate::{parse_logs, IndexerError, VotingEvent};
+use anchor_lang::prelude::Pubkey;
+use std::collections::BTreeMap;
+use voting::state::{PollOutcome, VotingMode};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CandidateView {
+    /// Standard votes, including delegated ones.
+    pub votes: u64,
+    /// Tokens currently locked on the candidate in conviction mode.
+    pub locked: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollView {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    pub description: String,
+    pub poll_start: u64,
+    pub poll_end: u64,
+    pub quorum: u64,
+    pub threshold_bps: u16,
+    pub total_votes: u64,
+    pub outcome: PollOutcome,
+    /// Prompt of each question; the first is the poll description.
+    pub questions: Vec<String>,
+    pub mode: VotingMode,
+    /// Mint locked in conviction mode; unused for standard polls.
+    pub stake_mint: Pubkey,
+    /// Winner of each question, filled in when the poll is finalized.
+    pub winners: Vec<Option<String>>,
+    /// Keyed by question index and normalized candidate name. Closed
+    /// candidates are removed.
+    pub candidates: BTreeMap<(u8, String), CandidateView>,
+    /// Vote receipts not yet closed, including those of delegators.
+    pub open_receipts: u64,
+    pub has_proposal: bool,
+    pub proposal_executed: bool,
+    /// Set once the poll account is closed; only its summary remains on chain.
+    pub closed: bool,
+    /// Candidate each open conviction lock is on, keyed by voter and question.
+    locks: BTreeMap<(Pubkey, u8), String>,
+}
+
+impl PollView {
+    fn is_locking(&self, voter: &Pubkey) -> bool {
+        self.locks.keys().any(|(locker, _)| locker == voter)
+    }
+
+    fn candidate(&mut self, question_index: u8, name: &str) -> &mut CandidateView {
+        self.candidates.entry((question_index, name.to_string())).or_default()
+    }
+}
+
+/// Polls rebuilt by replaying events. Events for polls created before
+/// indexing started are skipped, since their earlier history is unknown.
+#[derive(Clone, Debug, Default)]
+pub struct PollIndex {
+    polls: BTreeMap<u64, PollView>,
+}
+
+impl PollIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn poll(&self, poll_id: u64) -> Option<&PollView> {
+        self.polls.get(&poll_id)
+    }
+
+    pub fn polls(&self) -> impl Iterator<Item = &PollView> {
+        self.polls.values()
+    }
+
+    /// Applies the events found in one transaction's logs. Transactions must
+    /// be ingested in the order they were confirmed.
+    pub fn ingest<S: AsRef<str>>(&mut self, program_id: &Pubkey, logs: &[S]) -> Result<usize, IndexerError> {
+        let events = parse_logs(program_id, logs)?;
+        let count = events.len();
+        for event in events {
+            self.apply(event);
+        }
+        Ok(count)
+    }
+
+    pub fn apply(&mut self, event: VotingEvent) {
+        if let VotingEvent::PollCreated(created) = event {
+            self.polls.insert(created.poll_id, PollView {
+                poll_id: created.poll_id,
+                creator: created.creator,
+                description: created.description.clone(),
+                poll_start: created.poll_start,
+                poll_end: created.poll_end,
+                quorum: created.quorum,
+                threshold_bps: created.threshold_bps,
+                total_votes: 0,
+                outcome: PollOutcome::Pending,
+                questions: vec![created.description.clone()],
+                mode: VotingMode::Standard,
+                stake_mint: Pubkey::default(),
+                winners: Vec::new(),
+                candidates: BTreeMap::new(),
+                open_receipts: 0,
+                has_proposal: false,
+                proposal_executed: false,
+                closed: false,
+                locks: BTreeMap::new(),
+            });
+            return;
+        }
+
+        let poll_id = match &event {
+            VotingEvent::PollCreated(e) => e.poll_id,
+            VotingEvent::QuestionAdded(e) => e.poll_id,
+            VotingEvent::ConvictionEnabled(e) => e.poll_id,
+            VotingEvent::CandidateAdded(e) => e.poll_id,
+            VotingEvent::VoteCast(e) => e.poll_id,
+            VotingEvent::VoteChanged(e) => e.poll_id,
+            VotingEvent::VoteRetracted(e) => e.poll_id,
+            VotingEvent::PollFinalized(e) => e.poll_id,
+            VotingEvent::ProposalCreated(e) => e.poll_id,
+            VotingEvent::ProposalExecuted(e) => e.poll_id,
+            VotingEvent::TokensLocked(e) => e.poll_id,
+            VotingEvent::LockMoved(e) => e.poll_id,
+            VotingEvent::TokensUnlocked(e) => e.poll_id,
+            VotingEvent::CandidateClosed(e) => e.poll_id,
+            VotingEvent::ReceiptClosed(e) => e.poll_id,
+            VotingEvent::ProposalClosed(e) => e.poll_id,
+            VotingEvent::PollClosed(e) => e.poll_id,
+        };
+        let Some(poll) = self.polls.get_mut(&poll_id) else {
+            return;
+        };
+
+        match event {
+            VotingEvent::PollCreated(_) => {}
+            VotingEvent::QuestionAdded(e) => poll.questions.push(e.prompt),
+            VotingEvent::ConvictionEnabled(e) => {
+                poll.mode = VotingMode::Conviction;
+                poll.stake_mint = e.stake_mint;
+            }
+            VotingEvent::CandidateAdded(e) => {
+                poll.candidate(e.question_index, &e.candidate);
+            }
+            VotingEvent::VoteCast(e) => {
+                for (question_index, choice) in e.choices.iter().enumerate() {
+                    poll.candidate(question_index as u8, choice).votes += e.weight;
+                }
+                poll.total_votes += e.weight;
+                poll.open_receipts += 1 + e.delegators.len() as u64;
+            }
+            VotingEvent::VoteChanged(e) => {
+                let old = poll.candidate(e.question_index, &e.old_candidate);
+                old.votes = old.votes.saturating_sub(e.weight);
+                poll.candidate(e.question_index, &e.new_candidate).votes += e.weight;
+            }
+            VotingEvent::VoteRetracted(e) => {
+                for (question_index, choice) in e.choices.iter().enumerate() {
+                    let candidate = poll.candidate(question_index as u8, choice);
+                    candidate.votes = candidate.votes.saturating_sub(e.weight);
+                }
+                poll.total_votes = poll.total_votes.saturating_sub(e.weight);
+                // Delegators' receipts are closed along with the voter's.
+                poll.open_receipts = poll.open_receipts.saturating_sub(e.weight);
+            }
+            VotingEvent::PollFinalized(e) => {
+                poll.outcome = e.outcome;
+                poll.winners = e.winners;
+                poll.total_votes = e.total_votes;
+            }
+            VotingEvent::ProposalCreated(_) => poll.has_proposal = true,
+            VotingEvent::ProposalExecuted(_) => poll.proposal_executed = true,
+            VotingEvent::TokensLocked(e) => {
+                // A voter counts once, however many questions they lock on.
+                let counted = poll.is_locking(&e.voter);
+                poll.locks.insert((e.voter, e.question_index), e.candidate.clone());
+                if !counted {
+                    poll.total_votes += 1;
+                }
+                poll.candidate(e.question_index, &e.candidate).locked += e.amount;
+            }
+            VotingEvent::LockMoved(e) => {
+                // Only the tokens move in full; the conviction the lock keeps
+                // is in `e.conviction` but accrues with time, so it isn't tracked.
+                poll.locks.insert((e.voter, e.question_index), e.new_candidate.clone());
+                let old = poll.candidate(e.question_index, &e.old_candidate);
+                old.locked = old.locked.saturating_sub(e.amount);
+                poll.candidate(e.question_index, &e.new_candidate).locked += e.amount;
+            }
+            VotingEvent::TokensUnlocked(e) => {
+                poll.locks.remove(&(e.voter, e.question_index));
+                // Locks outlive their candidate once the poll is finalized.
+                if let Some(candidate) = poll.candidates.get_mut(&(e.question_index, e.candidate)) {
+                    candidate.locked = candidate.locked.saturating_sub(e.amount);
+                }
+                // The program only stops counting a locker while the poll is
+                // open; after `poll_end` the count is already final.
+                if e.uncounted && !poll.is_locking(&e.voter) {
+                    poll.total_votes = poll.total_votes.saturating_sub(1);
+                }
+            }
+            VotingEvent::CandidateClosed(e) => {
+                poll.candidates.remove(&(e.question_index, e.candidate));
+            }
+            VotingEvent::ReceiptClosed(_) => poll.open_receipts = poll.open_receipts.saturating_sub(1),
+            VotingEvent::ProposalClosed(_) => poll.has_proposal = false,
+            VotingEvent::PollClosed(_) => poll.closed = true,
+        }
+    }
+}
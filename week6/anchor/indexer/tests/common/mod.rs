@@ -0,0 +1,35 @@
+//! Synthetic voting program logs, written by hand in the format the runtime
+//! produces: events are Borsh-encoded from the program's event types, while
+//! compute units and the like are placeholders. Each fixture holds the logs
+//! of several transactions, separated by blank lines, in confirmation order.
+
+use anchor_lang::prelude::Pubkey;
+use voting_indexer::{IndexerError, PollIndex};
+
+pub const STANDARD_POLL_OPEN: &str = include_str!("../fixtures/standard_poll_open.txt");
+pub const STANDARD_POLL_CLOSED: &str = include_str!("../fixtures/standard_poll_closed.txt");
+pub const CONVICTION_POLL: &str = include_str!("../fixtures/conviction_poll.txt");
+
+/// Wallets used in the fixtures are filled with a single repeated byte.
+pub fn wallet(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+pub fn transactions(fixture: &str) -> Vec<Vec<&str>> {
+    fixture
+        .split("\n\n")
+        .map(|tx| tx.lines().filter(|line| !line.is_empty()).collect::<Vec<_>>())
+        .filter(|tx| !tx.is_empty())
+        .collect()
+}
+
+/// Ingests every transaction of the fixtures, returning the number of events applied.
+pub fn replay(index: &mut PollIndex, fixtures: &[&str]) -> Result<usize, IndexerError> {
+    let mut count = 0;
+    for fixture in fixtures {
+        for tx in transactions(fixture) {
+            count += index.ingest(&voting::ID, &tx)?;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use common::*;
+use voting::events::{CandidateClosed, TokensUnlocked};
+use voting::state::{PollOutcome, VotingMode};
+use voting_indexer::{parse_logs, CandidateView, IndexerError, PollIndex, VotingEvent};
+
+const TRUNCATED_EVENT: &str = include_str!("fixtures/truncated_event.txt");
+const UNKNOWN_EVENT: &str = include_str!("fixtures/unknown_event.txt");
+const INVALID_BASE64: &str = include_str!("fixtures/invalid_base64.txt");
+
+fn candidate(votes: u64, locked: u64) -> CandidateView {
+    CandidateView { votes, locked }
+}
+
+#[test]
+fn replays_a_standard_poll() {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[STANDARD_POLL_OPEN]).unwrap();
+
+    let poll = index.poll(1).unwrap();
+    assert_eq!(poll.creator, wallet(1));
+    assert_eq!(poll.questions, vec!["Favorite peanut butter?", "Favorite jelly?"]);
+    assert_eq!(poll.mode, VotingMode::Standard);
+    assert_eq!(poll.outcome, PollOutcome::Passed);
+    assert_eq!(poll.total_votes, 3);
+    assert_eq!(poll.winners, vec![Some("smooth".to_string()), Some("grape".to_string())]);
+    assert_eq!(poll.candidates[&(0, "smooth".to_string())], candidate(3, 0));
+    assert_eq!(poll.candidates[&(0, "crunchy".to_string())], candidate(0, 0));
+    assert_eq!(poll.candidates[&(1, "grape".to_string())], candidate(3, 0));
+    // Alice's and her delegator's receipts are closed; Carol's is left.
+    assert_eq!(poll.open_receipts, 1);
+    assert!(poll.has_proposal);
+    assert!(poll.proposal_executed);
+    assert!(!poll.closed);
+}
+
+#[test]
+fn replays_closing_a_poll() {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[STANDARD_POLL_OPEN, STANDARD_POLL_CLOSED]).unwrap();
+
+    let poll = index.poll(1).unwrap();
+    assert!(poll.candidates.is_empty());
+    assert_eq!(poll.open_receipts, 0);
+    assert!(!poll.has_proposal);
+    assert!(poll.closed);
+    // The result survives in the summary.
+    assert_eq!(poll.outcome, PollOutcome::Passed);
+    assert_eq!(poll.winners, vec![Some("smooth".to_string()), Some("grape".to_string())]);
+}
+
+#[test]
+fn replays_a_conviction_poll() {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[CONVICTION_POLL]).unwrap();
+
+    let poll = index.poll(2).unwrap();
+    assert_eq!(poll.mode, VotingMode::Conviction);
+    assert_eq!(poll.stake_mint, wallet(9));
+    assert_eq!(poll.questions.len(), 2);
+    assert_eq!(poll.candidates[&(0, "yes".to_string())], candidate(0, 1290));
+    assert_eq!(poll.candidates[&(0, "no".to_string())], candidate(0, 0));
+    assert_eq!(poll.candidates[&(1, "red".to_string())], candidate(0, 0));
+    // Alice still holds a lock after releasing one of her two, so both
+    // voters are counted once each.
+    assert_eq!(poll.total_votes, 2);
+}
+
+#[test]
+fn unlock_after_the_poll_ends_keeps_the_voter_counted() {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[CONVICTION_POLL]).unwrap();
+
+    // Bob unlocks after `poll_end` but before anyone finalizes the poll.
+    index.apply(VotingEvent::TokensUnlocked(TokensUnlocked {
+        poll_id: 2,
+        voter: wallet(3),
+        question_index: 0,
+        candidate: "yes".to_string(),
+        amount: 300,
+        uncounted: false,
+    }));
+
+    let poll = index.poll(2).unwrap();
+    assert_eq!(poll.outcome, PollOutcome::Pending);
+    assert_eq!(poll.candidates[&(0, "yes".to_string())], candidate(0, 990));
+    assert_eq!(poll.total_votes, 2);
+}
+
+#[test]
+fn unlock_after_the_candidate_closed_does_not_bring_it_back() {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[CONVICTION_POLL]).unwrap();
+
+    index.apply(VotingEvent::CandidateClosed(CandidateClosed {
+        poll_id: 2,
+        question_index: 0,
+        candidate: "yes".to_string(),
+    }));
+    index.apply(VotingEvent::TokensUnlocked(TokensUnlocked {
+        poll_id: 2,
+        voter: wallet(3),
+        question_index: 0,
+        candidate: "yes".to_string(),
+        amount: 300,
+        uncounted: false,
+    }));
+
+    assert!(!index.poll(2).unwrap().candidates.contains_key(&(0, "yes".to_string())));
+}
+
+#[test]
+fn moved_lock_reports_the_conviction_it_kept() {
+    let moved = transactions(CONVICTION_POLL)
+        .into_iter()
+        .flat_map(|tx| parse_logs(&voting::ID, &tx).unwrap())
+        .find_map(|event| match event {
+            VotingEvent::LockMoved(moved) => Some(moved),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(moved.voter, wallet(3));
+    assert_eq!((moved.old_candidate.as_str(), moved.new_candidate.as_str()), ("no", "yes"));
+    assert_eq!(moved.amount, 300);
+    assert_eq!(moved.conviction, 3_000);
+}
+
+#[test]
+fn failed_transactions_are_dropped() {
+    let failed = transactions(STANDARD_POLL_OPEN)
+        .into_iter()
+        .find(|tx| tx.last().unwrap().contains(" failed: "))
+        .unwrap();
+    assert!(parse_logs(&voting::ID, &failed).unwrap().is_empty());
+}
+
+#[test]
+fn data_logged_by_other_programs_is_ignored() {
+    let execute = transactions(STANDARD_POLL_OPEN)
+        .into_iter()
+        .find(|tx| tx.contains(&"Program log: Instruction: ExecuteProposal"))
+        .unwrap();
+    let events = parse_logs(&voting::ID, &execute).unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], VotingEvent::ProposalExecuted(_)));
+}
+
+#[test]
+fn events_of_unindexed_polls_are_skipped() {
+    let mut index = PollIndex::new();
+    let applied = replay(&mut index, &[STANDARD_POLL_CLOSED]).unwrap();
+    assert_eq!(applied, 6);
+    assert!(index.poll(1).is_none());
+}
+
+#[test]
+fn unknown_discriminators_are_skipped() {
+    let tx = &transactions(UNKNOWN_EVENT)[0];
+    let events = parse_logs(&voting::ID, tx).unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], VotingEvent::PollClosed(closed) if closed.poll_id == 1));
+}
+
+#[test]
+fn truncated_event_is_an_error() {
+    let tx = &transactions(TRUNCATED_EVENT)[0];
+    assert!(matches!(parse_logs(&voting::ID, tx), Err(IndexerError::Event(_))));
+}
+
+#[test]
+fn invalid_base64_is_an_error() {
+    let tx = &transactions(INVALID_BASE64)[0];
+    assert!(matches!(parse_logs(&voting::ID, tx), Err(IndexerError::Base64(_))));
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use common::*;
+use voting_indexer::sqlite::SqliteStore;
+use voting_indexer::PollIndex;
+
+/// `(poll_id, creator, total_votes, outcome, mode, open_receipts, has_proposal, proposal_executed, closed)`
+type PollRow = (u64, String, u64, String, String, u64, bool, bool, bool);
+
+fn stored_index() -> SqliteStore {
+    let mut index = PollIndex::new();
+    replay(&mut index, &[STANDARD_POLL_OPEN, CONVICTION_POLL]).unwrap();
+    let mut store = SqliteStore::open_in_memory().unwrap();
+    store.save(&index).unwrap();
+    store
+}
+
+#[test]
+fn saves_polls() {
+    let store = stored_index();
+    let mut query = store
+        .connection()
+        .prepare("SELECT poll_id, creator, total_votes, outcome, mode, open_receipts, has_proposal, proposal_executed, closed FROM polls ORDER BY poll_id")
+        .unwrap();
+    let rows: Vec<PollRow> = query
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+                row.get(8)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(rows, vec![
+        (1, wallet(1).to_string(), 3, "passed".into(), "standard".into(), 1, true, true, false),
+        (2, wallet(1).to_string(), 2, "pending".into(), "conviction".into(), 0, false, false, false),
+    ]);
+}
+
+#[test]
+fn saves_questions_and_candidates() {
+    let store = stored_index();
+    let conn = store.connection();
+
+    let prompts: Vec<String> = conn
+        .prepare("SELECT prompt FROM questions WHERE poll_id = 1 ORDER BY question_index")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(prompts, vec!["Favorite peanut butter?", "Favorite jelly?"]);
+
+    let candidates: Vec<(u8, String, u64, u64, bool)> = conn
+        .prepare("SELECT question_index, name, votes, locked, winner FROM candidates WHERE poll_id = 1 ORDER BY question_index, name")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(candidates, vec![
+        (0, "crunchy".into(), 0, 0, false),
+        (0, "smooth".into(), 3, 0, true),
+        (1, "grape".into(), 3, 0, true),
+    ]);
+}
+
+#[test]
+fn save_replaces_previous_state() {
+    let mut store = stored_index();
+    let mut index = PollIndex::new();
+    replay(&mut index, &[STANDARD_POLL_OPEN, STANDARD_POLL_CLOSED]).unwrap();
+    store.save(&index).unwrap();
+
+    let conn = store.connection();
+    let polls: u64 = conn.query_row("SELECT COUNT(*) FROM polls", [], |row| row.get(0)).unwrap();
+    let closed: bool = conn.query_row("SELECT closed FROM polls WHERE poll_id = 1", [], |row| row.get(0)).unwrap();
+    let candidates: u64 = conn.query_row("SELECT COUNT(*) FROM candidates", [], |row| row.get(0)).unwrap();
+    assert_eq!((polls, closed, candidates), (1, true, 0));
+}
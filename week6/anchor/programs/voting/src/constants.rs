@@ -0,0 +1,44 @@
+use anchor_lang::constant;
+
+#[constant]
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+#[constant]
+pub const DELEGATION_SEED: &[u8] = b"delegation";
+
+#[constant]
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+
+#[constant]
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+#[constant]
+pub const SUMMARY_SEED: &[u8] = b"summary";
+
+#[constant]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+#[constant]
+pub const LOCK_SEED: &[u8] = b"lock";
+
+#[constant]
+pub const VOTER_SEED: &[u8] = b"voter";
+
+#[constant]
+pub const MAX_THRESHOLD_BPS: u16 = 10_000;
+
+/// Share of a lock's conviction that follows it to another candidate; the
+/// rest is lost, so switching sides costs part of the weight built up.
+#[constant]
+pub const MOVE_CONVICTION_KEPT_BPS: u16 = 5_000;
+
+pub const MAX_QUESTIONS: usize = 8;
+
+pub const MAX_DESCRIPTION_LEN: usize = 280;
+
+/// Also the longest name that fits in a single PDA seed.
+pub const MAX_CANDIDATE_NAME_LEN: usize = 32;
+
+/// Seconds after `poll_end` before a poll's accounts can be closed.
+#[constant]
+pub const CLOSE_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
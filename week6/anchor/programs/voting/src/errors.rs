@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum VotingError {
+    #[msg("Voter has already voted in this poll")]
+    AlreadyVoted,
+    #[msg("Cannot delegate a vote to yourself")]
+    SelfDelegation,
+    #[msg("Delegation is not assigned to the signer")]
+    DelegateMismatch,
+    #[msg("Delegation does not cover this poll")]
+    DelegationScopeMismatch,
+    #[msg("Delegated votes must be passed as (delegation, receipt) account pairs")]
+    InvalidDelegationAccounts,
+    #[msg("Receipt account does not match the voter and poll")]
+    InvalidReceipt,
+    #[msg("Delegation has already been used to cast a vote")]
+    DelegationAlreadyUsed,
+    #[msg("Poll has not started yet")]
+    PollNotStarted,
+    #[msg("Poll has already ended")]
+    PollEnded,
+    #[msg("Vote was cast by a delegate and can only be changed by them")]
+    DelegatedVote,
+    #[msg("New candidate is the same as the current one")]
+    SameCandidate,
+    #[msg("Threshold must be at most 10000 basis points")]
+    InvalidThreshold,
+    #[msg("Poll has not ended yet")]
+    PollNotEnded,
+    #[msg("Poll has already been finalized")]
+    PollAlreadyFinalized,
+    #[msg("Every candidate of the poll must be passed exactly once")]
+    CandidateCountMismatch,
+    #[msg("Candidate account does not belong to this poll")]
+    InvalidCandidate,
+    #[msg("Only the poll creator can do this")]
+    NotPollCreator,
+    #[msg("Poll has already started")]
+    PollAlreadyStarted,
+    #[msg("Proposal needs at least one instruction")]
+    EmptyProposal,
+    #[msg("Poll has not passed")]
+    PollNotPassed,
+    #[msg("Proposal timelock has not elapsed")]
+    TimelockActive,
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Question does not exist in this poll")]
+    InvalidQuestion,
+    #[msg("Poll already has the maximum number of questions")]
+    TooManyQuestions,
+    #[msg("Ballot must answer every question, with one candidate account per answer")]
+    IncompleteBallot,
+    #[msg("Poll has not been finalized")]
+    PollNotFinalized,
+    #[msg("Grace period after the poll end has not passed")]
+    GracePeriodActive,
+    #[msg("Every candidate must be closed before the poll")]
+    CandidatesRemaining,
+    #[msg("Every vote receipt must be closed before the poll")]
+    ReceiptsRemaining,
+    #[msg("The poll's proposal must be closed before the poll")]
+    ProposalRemaining,
+    #[msg("Poll id belongs to a poll that was closed")]
+    PollIdRetired,
+    #[msg("Rent must be returned to the account that paid it")]
+    InvalidRentReceiver,
+    #[msg("Description must not be empty")]
+    DescriptionEmpty,
+    #[msg("Description must be at most 280 bytes")]
+    DescriptionTooLong,
+    #[msg("Candidate name must not be empty")]
+    CandidateNameEmpty,
+    #[msg("Candidate name must be at most 32 bytes")]
+    CandidateNameTooLong,
+    #[msg("Candidate is already registered for this question")]
+    DuplicateCandidate,
+    #[msg("Instruction is not available in this poll's voting mode")]
+    WrongVotingMode,
+    #[msg("Locked amount must be greater than zero")]
+    InvalidLockAmount,
+    #[msg("Tokens are locked on another candidate; move the lock instead")]
+    LockCandidateMismatch,
+}
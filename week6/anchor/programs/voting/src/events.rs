@@ -0,0 +1,140 @@
+use crate::state::PollOutcome;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PollCreated {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    pub description: String,
+    pub poll_start: u64,
+    pub poll_end: u64,
+    pub quorum: u64,
+    pub threshold_bps: u16,
+}
+
+#[event]
+pub struct QuestionAdded {
+    pub poll_id: u64,
+    pub question_index: u8,
+    pub prompt: String,
+}
+
+#[event]
+pub struct ConvictionEnabled {
+    pub poll_id: u64,
+    pub stake_mint: Pubkey,
+}
+
+#[event]
+pub struct CandidateAdded {
+    pub poll_id: u64,
+    pub question_index: u8,
+    pub candidate: String,
+}
+
+#[event]
+pub struct VoteCast {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    /// Chosen candidate for each question, in order.
+    pub choices: Vec<String>,
+    /// Votes added to each chosen candidate, including delegated ones.
+    pub weight: u64,
+    /// Delegators whose votes are counted in `weight`.
+    pub delegators: Vec<Pubkey>,
+}
+
+#[event]
+pub struct VoteChanged {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub question_index: u8,
+    pub old_candidate: String,
+    pub new_candidate: String,
+    pub weight: u64,
+}
+
+#[event]
+pub struct VoteRetracted {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub choices: Vec<String>,
+    pub weight: u64,
+}
+
+#[event]
+pub struct PollFinalized {
+    pub poll_id: u64,
+    pub outcome: PollOutcome,
+    pub winners: Vec<Option<String>>,
+    pub total_votes: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub poll_id: u64,
+    pub instruction_count: u64,
+    pub timelock: u64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub poll_id: u64,
+    pub instruction_count: u64,
+}
+
+#[event]
+pub struct TokensLocked {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub question_index: u8,
+    pub candidate: String,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LockMoved {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub question_index: u8,
+    pub old_candidate: String,
+    pub new_candidate: String,
+    pub amount: u64,
+    /// Conviction the lock kept through the move, in token-seconds.
+    pub conviction: u128,
+}
+
+#[event]
+pub struct TokensUnlocked {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub question_index: u8,
+    pub candidate: String,
+    pub amount: u64,
+    /// Set when the poll was still open, so the lock's conviction was withdrawn
+    /// and the voter stopped counting once this was their last lock.
+    pub uncounted: bool,
+}
+
+#[event]
+pub struct CandidateClosed {
+    pub poll_id: u64,
+    pub question_index: u8,
+    pub candidate: String,
+}
+
+#[event]
+pub struct ReceiptClosed {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub poll_id: u64,
+}
+
+#[event]
+pub struct PollClosed {
+    pub poll_id: u64,
+}
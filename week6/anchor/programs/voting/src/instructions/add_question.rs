@@ -0,0 +1,44 @@
+use crate::constants::MAX_QUESTIONS;
+use crate::errors::VotingError;
+use crate::events::QuestionAdded;
+use crate::state::{Poll, Question};
+use crate::utils::validate_description;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct AddQuestion<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+}
+
+/// Questions can only be added before voting starts, since every ballot has
+/// to answer all of them.
+pub fn add_question(ctx: Context<AddQuestion>, poll_id: u64, prompt: String) -> Result<()> {
+    validate_description(&prompt)?;
+
+    let poll = &mut ctx.accounts.poll;
+    let now = Clock::get()?.unix_timestamp.max(0) as u64;
+    require_gt!(poll.poll_start, now, VotingError::PollAlreadyStarted);
+    require_gt!(MAX_QUESTIONS, poll.questions.len(), VotingError::TooManyQuestions);
+
+    poll.questions.push(Question {
+        prompt: prompt.clone(),
+        candidate_amount: 0,
+        winner: None,
+    });
+
+    emit!(QuestionAdded {
+        poll_id,
+        question_index: (poll.questions.len() - 1) as u8,
+        prompt,
+    });
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::constants::RECEIPT_SEED;
+use crate::errors::VotingError;
+use crate::events::VoteChanged;
+use crate::state::{Candidate, Poll, VoteReceipt};
+use crate::utils::{candidate_seed, normalize_candidate_name};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(question_index: u8, new_candidate_name: String, poll_id: u64)]
+pub struct ChangeVote<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump = receipt.bump,
+        constraint = receipt.delegate.is_none() @ VotingError::DelegatedVote,
+        constraint = (question_index as usize) < receipt.choices.len() @ VotingError::InvalidQuestion,
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            receipt.choice_seed(question_index),
+        ],
+        bump = old_candidate.bump
+    )]
+    pub old_candidate: Account<'info, Candidate>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            candidate_seed(&new_candidate_name).as_ref(),
+        ],
+        bump = new_candidate.bump
+    )]
+    pub new_candidate: Account<'info, Candidate>,
+}
+
+pub fn change_vote(ctx: Context<ChangeVote>,
+                   question_index: u8,
+                   new_candidate_name: String,
+                   poll_id: u64) -> Result<()> {
+    ctx.accounts.poll.require_open(Clock::get()?.unix_timestamp)?;
+    require_keys_neq!(
+        ctx.accounts.old_candidate.key(),
+        ctx.accounts.new_candidate.key(),
+        VotingError::SameCandidate
+    );
+
+    let new_candidate_name = normalize_candidate_name(&new_candidate_name);
+    let receipt = &mut ctx.accounts.receipt;
+    let weight = receipt.weight;
+    ctx.accounts.old_candidate.candidate_votes -= weight;
+    ctx.accounts.new_candidate.candidate_votes += weight;
+
+    let old_candidate = std::mem::replace(
+        &mut receipt.choices[question_index as usize],
+        new_candidate_name.clone(),
+    );
+
+    emit!(VoteChanged {
+        poll_id,
+        voter: receipt.voter,
+        question_index,
+        old_candidate,
+        new_candidate: new_candidate_name,
+        weight,
+    });
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::errors::VotingError;
+use crate::events::CandidateClosed;
+use crate::state::{Candidate, Poll};
+use crate::utils::candidate_seed;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, question_index: u8, candidate_name: String)]
+pub struct CloseCandidate<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            candidate_seed(&candidate_name).as_ref(),
+        ],
+        bump = candidate.bump,
+        close = payer,
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    /// CHECK: only receives lamports; must be the candidate's payer.
+    #[account(
+        mut,
+        address = candidate.payer @ VotingError::InvalidRentReceiver,
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Tokens still locked on the candidate don't hold it open: the result is
+/// final by now, and `unlock_tokens` works without the candidate.
+pub fn close_candidate(ctx: Context<CloseCandidate>,
+                       poll_id: u64,
+                       question_index: u8,
+                       _candidate_name: String) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    poll.require_closable(Clock::get()?.unix_timestamp)?;
+
+    poll.candidate_amount -= 1;
+    if let Some(question) = poll.questions.get_mut(question_index as usize) {
+        question.candidate_amount -= 1;
+    }
+
+    emit!(CandidateClosed {
+        poll_id,
+        question_index,
+        candidate: ctx.accounts.candidate.candidate_name.clone(),
+    });
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::constants::SUMMARY_SEED;
+use crate::errors::VotingError;
+use crate::events::PollClosed;
+use crate::state::{Poll, PollSummary};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct ClosePoll<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+        close = signer,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + PollSummary::INIT_SPACE,
+        seeds = [SUMMARY_SEED, poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub summary: Account<'info, PollSummary>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Candidates, vote receipts and the proposal have to be closed first, so no
+/// rent is stranded and nothing of the poll outlives it but the summary.
+/// Conviction locks are the exception: their owners close them with
+/// `reclaim_lock`, which only needs the summary and the vault.
+pub fn close_poll(ctx: Context<ClosePoll>, poll_id: u64) -> Result<()> {
+    let poll = &ctx.accounts.poll;
+    poll.require_closable(Clock::get()?.unix_timestamp)?;
+    require_eq!(poll.candidate_amount, 0, VotingError::CandidatesRemaining);
+    require_eq!(poll.open_receipts, 0, VotingError::ReceiptsRemaining);
+    require!(!poll.has_proposal, VotingError::ProposalRemaining);
+
+    ctx.accounts.summary.set_inner(PollSummary {
+        poll_id,
+        creator: poll.creator,
+        outcome: poll.outcome,
+        total_votes: poll.total_votes,
+        finalized_at: poll.finalized_at,
+        winners: poll.questions.iter().map(|question| question.winner.clone()).collect(),
+    });
+
+    emit!(PollClosed { poll_id });
+    Ok(())
+}
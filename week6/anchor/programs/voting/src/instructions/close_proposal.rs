@@ -0,0 +1,39 @@
+use crate::constants::PROPOSAL_SEED;
+use crate::errors::VotingError;
+use crate::events::ProposalClosed;
+use crate::state::{Poll, Proposal};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct CloseProposal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, poll_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = signer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Executed or not, a proposal is closed with the rest of its poll. One still
+/// waiting on its timelock can't execute afterwards.
+pub fn close_proposal(ctx: Context<CloseProposal>, poll_id: u64) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    poll.require_closable(Clock::get()?.unix_timestamp)?;
+    poll.has_proposal = false;
+
+    emit!(ProposalClosed { poll_id });
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::constants::RECEIPT_SEED;
+use crate::errors::VotingError;
+use crate::events::ReceiptClosed;
+use crate::state::{Poll, VoteReceipt};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, voter: Pubkey)]
+pub struct CloseReceipt<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, poll_id.to_le_bytes().as_ref(), voter.as_ref()],
+        bump = receipt.bump,
+        close = payer,
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+
+    /// CHECK: only receives lamports; must be whoever paid for the receipt.
+    #[account(
+        mut,
+        address = receipt.payer() @ VotingError::InvalidRentReceiver,
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn close_receipt(ctx: Context<CloseReceipt>, poll_id: u64, voter: Pubkey) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    poll.require_closable(Clock::get()?.unix_timestamp)?;
+    poll.open_receipts -= 1;
+
+    emit!(ReceiptClosed { poll_id, voter });
+    Ok(())
+}
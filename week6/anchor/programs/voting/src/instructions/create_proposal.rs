@@ -0,0 +1,57 @@
+use crate::constants::PROPOSAL_SEED;
+use crate::errors::VotingError;
+use crate::events::ProposalCreated;
+use crate::state::{Poll, Proposal, ProposalInstruction};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, instructions: Vec<ProposalInstruction>)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Proposal::space(&instructions),
+        seeds = [PROPOSAL_SEED, poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Proposals must be attached before voting starts, so every voter sees
+/// exactly what a passing vote will execute.
+pub fn create_proposal(ctx: Context<CreateProposal>,
+                       poll_id: u64,
+                       instructions: Vec<ProposalInstruction>,
+                       timelock: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.max(0) as u64;
+    require_gt!(ctx.accounts.poll.poll_start, now, VotingError::PollAlreadyStarted);
+    require!(!instructions.is_empty(), VotingError::EmptyProposal);
+
+    ctx.accounts.poll.has_proposal = true;
+    emit!(ProposalCreated {
+        poll_id,
+        instruction_count: instructions.len() as u64,
+        timelock,
+    });
+    ctx.accounts.proposal.set_inner(Proposal {
+        poll_id,
+        instructions,
+        timelock,
+        executed: false,
+        bump: ctx.bumps.proposal,
+    });
+    Ok(())
+}
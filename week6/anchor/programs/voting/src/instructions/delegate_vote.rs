@@ -0,0 +1,38 @@
+use crate::constants::DELEGATION_SEED;
+use crate::errors::VotingError;
+use crate::state::{Delegation, DelegationScope};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(scope: DelegationScope)]
+pub struct DelegateVote<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = delegator,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [DELEGATION_SEED, delegator.key().as_ref(), scope.seed().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn delegate_vote(ctx: Context<DelegateVote>,
+                     scope: DelegationScope,
+                     delegate: Pubkey) -> Result<()> {
+    let delegator = ctx.accounts.delegator.key();
+    require_keys_neq!(delegator, delegate, VotingError::SelfDelegation);
+
+    ctx.accounts.delegation.set_inner(Delegation {
+        delegator,
+        delegate,
+        scope,
+        used: false,
+        bump: ctx.bumps.delegation,
+    });
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::constants::VAULT_SEED;
+use crate::errors::VotingError;
+use crate::events::ConvictionEnabled;
+use crate::state::{Poll, VotingMode};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct EnableConviction<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.creator == signer.key() @ VotingError::NotPollCreator,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [VAULT_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Switches a poll to conviction mode before it starts, creating the vault
+/// that holds locked tokens.
+pub fn enable_conviction(ctx: Context<EnableConviction>, poll_id: u64) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    let now = Clock::get()?.unix_timestamp.max(0) as u64;
+    require_gt!(poll.poll_start, now, VotingError::PollAlreadyStarted);
+
+    poll.mode = VotingMode::Conviction;
+    poll.stake_mint = ctx.accounts.stake_mint.key();
+
+    emit!(ConvictionEnabled {
+        poll_id,
+        stake_mint: poll.stake_mint,
+    });
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::constants::{PROPOSAL_SEED, TREASURY_SEED};
+use crate::errors::VotingError;
+use crate::events::ProposalExecuted;
+use crate::state::{Poll, PollOutcome, Proposal};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct ExecuteProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, poll_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: lamport-only PDA owned by this poll alone, so a proposal can't
+    /// spend funds that another poll's voters govern. It signs the proposal's
+    /// instructions.
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED, poll.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+pub fn execute_proposal<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+                               poll_id: u64) -> Result<()> {
+    let poll = &ctx.accounts.poll;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(poll.outcome == PollOutcome::Passed, VotingError::PollNotPassed);
+    require!(!proposal.executed, VotingError::ProposalAlreadyExecuted);
+
+    let now = Clock::get()?.unix_timestamp.max(0) as u64;
+    let unlocks_at = poll.finalized_at.saturating_add(proposal.timelock);
+    require_gte!(now, unlocks_at, VotingError::TimelockActive);
+
+    // Marked before invoking so a proposal that calls back into this program
+    // can't run itself twice.
+    proposal.executed = true;
+    proposal.exit(ctx.program_id)?;
+
+    let treasury = ctx.accounts.treasury.to_account_info();
+    let mut account_infos = Vec::with_capacity(ctx.remaining_accounts.len() + 1);
+    account_infos.push(treasury.clone());
+    account_infos.extend_from_slice(ctx.remaining_accounts);
+
+    let poll_key = poll.key();
+    let treasury_seeds: &[&[u8]] = &[TREASURY_SEED, poll_key.as_ref(), &[ctx.bumps.treasury]];
+    for instruction in &proposal.instructions {
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect();
+        invoke_signed(
+            &Instruction {
+                program_id: instruction.program_id,
+                accounts,
+                data: instruction.data.clone(),
+            },
+            &account_infos,
+            &[treasury_seeds],
+        )?;
+    }
+
+    emit!(ProposalExecuted {
+        poll_id,
+        instruction_count: proposal.instructions.len() as u64,
+    });
+    Ok(())
+}
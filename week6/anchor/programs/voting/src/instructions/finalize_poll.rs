@@ -0,0 +1,53 @@
+use crate::errors::VotingError;
+use crate::events::PollFinalized;
+use crate::state::{Candidate, Poll, PollOutcome};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct FinalizePoll<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+}
+
+pub fn finalize_poll<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizePoll<'info>>,
+                            poll_id: u64) -> Result<PollOutcome> {
+    let poll = &mut ctx.accounts.poll;
+    let now = Clock::get()?.unix_timestamp.max(0) as u64;
+    require_gt!(now, poll.poll_end, VotingError::PollNotEnded);
+    require!(poll.outcome == PollOutcome::Pending, VotingError::PollAlreadyFinalized);
+    require_eq!(
+        ctx.remaining_accounts.len() as u64,
+        poll.candidate_amount,
+        VotingError::CandidateCountMismatch
+    );
+
+    let mut candidates = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut seen = Vec::with_capacity(ctx.remaining_accounts.len());
+    for info in ctx.remaining_accounts {
+        let candidate = Account::<Candidate>::try_from(info)?;
+        candidate.verify_address(info.key, poll_id, ctx.program_id)?;
+        require!(!seen.contains(info.key), VotingError::CandidateCountMismatch);
+        seen.push(info.key());
+        candidates.push(candidate);
+    }
+
+    let candidates: Vec<&Candidate> = candidates.iter().map(|candidate| &**candidate).collect();
+    let outcome = poll.tally(&candidates);
+    poll.outcome = outcome;
+    poll.finalized_at = now;
+
+    emit!(PollFinalized {
+        poll_id,
+        outcome,
+        winners: poll.questions.iter().map(|question| question.winner.clone()).collect(),
+        total_votes: poll.total_votes,
+    });
+    Ok(outcome)
+}
@@ -0,0 +1,63 @@
+use crate::errors::VotingError;
+use crate::events::CandidateAdded;
+use crate::state::{Candidate, Poll};
+use crate::utils::{candidate_seed, validate_candidate_name};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(candidate_name: String, poll_id: u64, question_index: u8)]
+pub struct InitializeCandidate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Candidate::INIT_SPACE,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            candidate_seed(&candidate_name).as_ref(),
+        ],
+        bump
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_candidate(ctx: Context<InitializeCandidate>,
+                            candidate_name: String,
+                            poll_id: u64,
+                            question_index: u8) -> Result<()> {
+    let candidate_name = validate_candidate_name(&candidate_name)?;
+    let candidate = &mut ctx.accounts.candidate;
+    require!(candidate.candidate_name.is_empty(), VotingError::DuplicateCandidate);
+
+    let poll = &mut ctx.accounts.poll;
+    let question = poll
+        .questions
+        .get_mut(question_index as usize)
+        .ok_or(VotingError::InvalidQuestion)?;
+    question.candidate_amount += 1;
+    poll.candidate_amount += 1;
+    candidate.candidate_name = candidate_name.clone();
+    candidate.candidate_votes = 0;
+    candidate.question_index = question_index;
+    candidate.payer = ctx.accounts.signer.key();
+    candidate.bump = ctx.bumps.candidate;
+
+    emit!(CandidateAdded {
+        poll_id,
+        question_index,
+        candidate: candidate_name,
+    });
+    Ok(())
+}
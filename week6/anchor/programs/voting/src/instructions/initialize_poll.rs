@@ -0,0 +1,76 @@
+use crate::constants::{MAX_THRESHOLD_BPS, SUMMARY_SEED};
+use crate::errors::VotingError;
+use crate::events::PollCreated;
+use crate::state::{Poll, PollOutcome, Question, VotingMode};
+use crate::utils::validate_description;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct InitializePoll<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Poll::INIT_SPACE,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+
+    /// CHECK: only checked to be empty; a summary here means the id was used
+    /// by a poll that has since been closed.
+    #[account(
+        seeds = [SUMMARY_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = summary.data_is_empty() @ VotingError::PollIdRetired,
+    )]
+    pub summary: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_poll(ctx: Context<InitializePoll>,
+                       poll_id: u64,
+                       description: String,
+                       poll_start: u64,
+                       poll_end: u64,
+                       quorum: u64,
+                       threshold_bps: u16) -> Result<()> {
+    validate_description(&description)?;
+    require_gte!(MAX_THRESHOLD_BPS, threshold_bps, VotingError::InvalidThreshold);
+
+    let poll = &mut ctx.accounts.poll;
+    poll.poll_id = poll_id;
+    poll.questions = vec![Question {
+        prompt: description.clone(),
+        candidate_amount: 0,
+        winner: None,
+    }];
+    poll.description = description;
+    poll.poll_start = poll_start;
+    poll.poll_end = poll_end;
+    poll.candidate_amount = 0;
+    poll.creator = ctx.accounts.signer.key();
+    poll.quorum = quorum;
+    poll.threshold_bps = threshold_bps;
+    poll.total_votes = 0;
+    poll.outcome = PollOutcome::Pending;
+    poll.finalized_at = 0;
+    poll.mode = VotingMode::Standard;
+    poll.stake_mint = Pubkey::default();
+    poll.open_receipts = 0;
+    poll.has_proposal = false;
+
+    emit!(PollCreated {
+        poll_id,
+        creator: poll.creator,
+        description: poll.description.clone(),
+        poll_start,
+        poll_end,
+        quorum,
+        threshold_bps,
+    });
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use crate::constants::{LOCK_SEED, VAULT_SEED, VOTER_SEED};
+use crate::errors::VotingError;
+use crate::events::TokensLocked;
+use crate::state::{Candidate, ConvictionLock, ConvictionVoter, Poll, VotingMode};
+use crate::utils::{candidate_seed, normalize_candidate_name};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, question_index: u8, candidate_name: String)]
+pub struct LockTokens<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.mode == VotingMode::Conviction @ VotingError::WrongVotingMode,
+    )]
+    pub poll: Box<Account<'info, Poll>>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            candidate_seed(&candidate_name).as_ref(),
+        ],
+        bump = candidate.bump
+    )]
+    pub candidate: Account<'info, Candidate>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + ConvictionLock::INIT_SPACE,
+        seeds = [
+            LOCK_SEED,
+            poll_id.to_le_bytes().as_ref(),
+            signer.key().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub lock: Account<'info, ConvictionLock>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + ConvictionVoter::INIT_SPACE,
+        seeds = [VOTER_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub conviction_voter: Account<'info, ConvictionVoter>,
+
+    #[account(
+        address = poll.stake_mint,
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Locks tokens on a candidate, or adds to an existing lock on the same one.
+/// The lock is credited with what the vault receives, which is less than
+/// `amount` for mints with a transfer fee.
+pub fn lock_tokens(ctx: Context<LockTokens>,
+                   poll_id: u64,
+                   question_index: u8,
+                   candidate_name: String,
+                   amount: u64) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    let now = Clock::get()?.unix_timestamp;
+    poll.require_open(now)?;
+    require_gt!(amount, 0, VotingError::InvalidLockAmount);
+
+    let candidate_name = normalize_candidate_name(&candidate_name);
+    let lock = &mut ctx.accounts.lock;
+    if lock.voter == Pubkey::default() {
+        lock.poll_id = poll_id;
+        lock.voter = ctx.accounts.signer.key();
+        lock.question_index = question_index;
+        lock.candidate_name = candidate_name.clone();
+        lock.bump = ctx.bumps.lock;
+
+        let conviction_voter = &mut ctx.accounts.conviction_voter;
+        if conviction_voter.active_locks == 0 {
+            conviction_voter.poll_id = poll_id;
+            conviction_voter.voter = lock.voter;
+            conviction_voter.bump = ctx.bumps.conviction_voter;
+            poll.total_votes += 1;
+        }
+        conviction_voter.active_locks += 1;
+    }
+    require!(lock.candidate_name == candidate_name, VotingError::LockCandidateMismatch);
+
+    let vault_before = ctx.accounts.vault.amount;
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.voter_token_account.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+    ctx.accounts.vault.reload()?;
+    let received = ctx.accounts.vault.amount - vault_before;
+
+    let at = poll.conviction_clock(now);
+    let candidate = &mut ctx.accounts.candidate;
+    candidate.accrue_conviction(at);
+    lock.accrue_conviction(at);
+    candidate.conviction_amount += received;
+    lock.amount += received;
+
+    emit!(TokensLocked {
+        poll_id,
+        voter: lock.voter,
+        question_index,
+        candidate: candidate_name,
+        amount: received,
+    });
+    Ok(())
+}
@@ -0,0 +1,41 @@
+pub mod add_question;
+pub mod change_vote;
+pub mod close_candidate;
+pub mod close_poll;
+pub mod close_proposal;
+pub mod close_receipt;
+pub mod create_proposal;
+pub mod delegate_vote;
+pub mod enable_conviction;
+pub mod execute_proposal;
+pub mod finalize_poll;
+pub mod initialize_candidate;
+pub mod initialize_poll;
+pub mod lock_tokens;
+pub mod move_lock;
+pub mod reclaim_lock;
+pub mod retract_vote;
+pub mod revoke_delegation;
+pub mod unlock_tokens;
+pub mod vote;
+
+pub use add_question::*;
+pub use change_vote::*;
+pub use close_candidate::*;
+pub use close_poll::*;
+pub use close_proposal::*;
+pub use close_receipt::*;
+pub use create_proposal::*;
+pub use delegate_vote::*;
+pub use enable_conviction::*;
+pub use execute_proposal::*;
+pub use finalize_poll::*;
+pub use initialize_candidate::*;
+pub use initialize_poll::*;
+pub use lock_tokens::*;
+pub use move_lock::*;
+pub use reclaim_lock::*;
+pub use retract_vote::*;
+pub use revoke_delegation::*;
+pub use unlock_tokens::*;
+pub use vote::*;
@@ -0,0 +1,87 @@
+use crate::constants::LOCK_SEED;
+use crate::errors::VotingError;
+use crate::events::LockMoved;
+use crate::state::{Candidate, ConvictionLock, Poll, VotingMode};
+use crate::utils::{candidate_seed, normalize_candidate_name};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, question_index: u8, new_candidate_name: String)]
+pub struct MoveLock<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.mode == VotingMode::Conviction @ VotingError::WrongVotingMode,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOCK_SEED,
+            poll_id.to_le_bytes().as_ref(),
+            signer.key().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+        ],
+        bump = lock.bump
+    )]
+    pub lock: Account<'info, ConvictionLock>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            lock.candidate_name.as_bytes(),
+        ],
+        bump = old_candidate.bump
+    )]
+    pub old_candidate: Account<'info, Candidate>,
+
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            candidate_seed(&new_candidate_name).as_ref(),
+        ],
+        bump = new_candidate.bump
+    )]
+    pub new_candidate: Account<'info, Candidate>,
+}
+
+/// Moves locked tokens to another candidate. Conviction decays on the way:
+/// only `MOVE_CONVICTION_KEPT_BPS` of what the lock built up on the old
+/// candidate is credited to the new one.
+pub fn move_lock(ctx: Context<MoveLock>,
+                 poll_id: u64,
+                 question_index: u8,
+                 new_candidate_name: String) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.poll.require_open(now)?;
+    require_keys_neq!(
+        ctx.accounts.old_candidate.key(),
+        ctx.accounts.new_candidate.key(),
+        VotingError::SameCandidate
+    );
+
+    let at = ctx.accounts.poll.conviction_clock(now);
+    let lock = &mut ctx.accounts.lock;
+    lock.move_to(&mut ctx.accounts.old_candidate, &mut ctx.accounts.new_candidate, at);
+
+    let new_candidate_name = normalize_candidate_name(&new_candidate_name);
+    let old_candidate_name = std::mem::replace(&mut lock.candidate_name, new_candidate_name.clone());
+
+    emit!(LockMoved {
+        poll_id,
+        voter: lock.voter,
+        question_index,
+        old_candidate: old_candidate_name,
+        new_candidate: new_candidate_name,
+        amount: lock.amount,
+        conviction: lock.score,
+    });
+    Ok(())
+}
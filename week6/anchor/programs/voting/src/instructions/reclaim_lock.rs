@@ -0,0 +1,100 @@
+use crate::constants::{LOCK_SEED, SUMMARY_SEED, VAULT_SEED, VOTER_SEED};
+use crate::events::TokensUnlocked;
+use crate::state::{ConvictionLock, ConvictionVoter, PollSummary};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, question_index: u8)]
+pub struct ReclaimLock<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Only exists once the poll account is closed.
+    #[account(
+        seeds = [SUMMARY_SEED, poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub summary: Account<'info, PollSummary>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOCK_SEED,
+            poll_id.to_le_bytes().as_ref(),
+            signer.key().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+        ],
+        bump = lock.bump,
+        close = signer,
+    )]
+    pub lock: Account<'info, ConvictionLock>,
+
+    #[account(
+        mut,
+        seeds = [VOTER_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump = conviction_voter.bump
+    )]
+    pub conviction_voter: Account<'info, ConvictionVoter>,
+
+    // The poll that recorded the mint is gone; the vault still holds it.
+    #[account(
+        address = vault.mint,
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Returns tokens still locked when the creator closed the poll. The result
+/// is in the summary by then, so nothing is recounted.
+pub fn reclaim_lock(ctx: Context<ReclaimLock>, poll_id: u64, question_index: u8) -> Result<()> {
+    let lock = &ctx.accounts.lock;
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, &poll_id.to_le_bytes(), &[ctx.bumps.vault]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.voter_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        lock.amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    emit!(TokensUnlocked {
+        poll_id,
+        voter: lock.voter,
+        question_index,
+        candidate: lock.candidate_name.clone(),
+        amount: lock.amount,
+        uncounted: false,
+    });
+
+    let conviction_voter = &mut ctx.accounts.conviction_voter;
+    conviction_voter.active_locks -= 1;
+    if conviction_voter.active_locks == 0 {
+        conviction_voter.close(ctx.accounts.signer.to_account_info())?;
+    }
+    Ok(())
+}
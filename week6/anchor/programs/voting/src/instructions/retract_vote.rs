@@ -0,0 +1,82 @@
+use crate::constants::RECEIPT_SEED;
+use crate::errors::VotingError;
+use crate::events::VoteRetracted;
+use crate::state::{Candidate, Poll, VoteReceipt};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct RetractVote<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump = receipt.bump,
+        constraint = receipt.delegate.is_none() @ VotingError::DelegatedVote,
+        close = signer,
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+}
+
+/// Remaining accounts are the chosen candidates, in question order, then the
+/// receipt of every delegator counted by this vote.
+///
+/// Closing the receipt lets the signer vote again while the poll is open.
+/// Delegated weight is retracted with it and the delegators' receipts are
+/// closed too, so they can vote themselves; a poll-scoped delegation stays used.
+pub fn retract_vote<'info>(ctx: Context<'_, '_, 'info, 'info, RetractVote<'info>>,
+                           poll_id: u64) -> Result<()> {
+    ctx.accounts.poll.require_open(Clock::get()?.unix_timestamp)?;
+
+    let receipt = &ctx.accounts.receipt;
+    let delegator_count = receipt.weight - 1;
+    require_eq!(
+        ctx.remaining_accounts.len() as u64,
+        receipt.choices.len() as u64 + delegator_count,
+        VotingError::IncompleteBallot
+    );
+    let (candidate_infos, receipt_infos) = ctx.remaining_accounts.split_at(receipt.choices.len());
+
+    for (question_index, (choice, info)) in receipt.choices.iter().zip(candidate_infos).enumerate() {
+        let mut candidate = Account::<Candidate>::try_from(info)?;
+        candidate.verify_address(info.key, poll_id, ctx.program_id)?;
+        require!(
+            candidate.question_index as usize == question_index && candidate.candidate_name == *choice,
+            VotingError::InvalidCandidate
+        );
+        candidate.candidate_votes -= receipt.weight;
+        candidate.exit(ctx.program_id)?;
+    }
+
+    let signer = ctx.accounts.signer.to_account_info();
+    for info in receipt_infos {
+        // Only receipts this signer created as delegate qualify; they were
+        // paid for by the delegate, who gets the rent back.
+        let delegator_receipt = Account::<VoteReceipt>::try_from(info)?;
+        require!(
+            delegator_receipt.poll_id == poll_id && delegator_receipt.delegate == Some(signer.key()),
+            VotingError::InvalidReceipt
+        );
+        delegator_receipt.close(signer.clone())?;
+    }
+
+    ctx.accounts.poll.total_votes -= receipt.weight;
+    ctx.accounts.poll.open_receipts -= receipt.weight;
+
+    emit!(VoteRetracted {
+        poll_id,
+        voter: receipt.voter,
+        choices: receipt.choices.clone(),
+        weight: receipt.weight,
+    });
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::constants::DELEGATION_SEED;
+use crate::errors::VotingError;
+use crate::state::{Delegation, DelegationScope};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(scope: DelegationScope)]
+pub struct RevokeDelegation<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [DELEGATION_SEED, delegator.key().as_ref(), scope.seed().as_ref()],
+        bump = delegation.bump,
+        has_one = delegator,
+        close = delegator,
+    )]
+    pub delegation: Account<'info, Delegation>,
+}
+
+/// Revoking only affects votes that haven't been cast yet: delegators already
+/// counted by their delegate keep their receipt for that poll.
+pub fn revoke_delegation(ctx: Context<RevokeDelegation>, _scope: DelegationScope) -> Result<()> {
+    require!(!ctx.accounts.delegation.used, VotingError::DelegationAlreadyUsed);
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::constants::{LOCK_SEED, VAULT_SEED, VOTER_SEED};
+use crate::errors::VotingError;
+use crate::events::TokensUnlocked;
+use crate::state::{Candidate, ConvictionLock, ConvictionVoter, Poll, PollOutcome, VotingMode};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64, question_index: u8)]
+pub struct UnlockTokens<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.mode == VotingMode::Conviction @ VotingError::WrongVotingMode,
+    )]
+    pub poll: Box<Account<'info, Poll>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOCK_SEED,
+            poll_id.to_le_bytes().as_ref(),
+            signer.key().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+        ],
+        bump = lock.bump,
+        close = signer,
+    )]
+    pub lock: Account<'info, ConvictionLock>,
+
+    #[account(
+        mut,
+        seeds = [VOTER_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump = conviction_voter.bump
+    )]
+    pub conviction_voter: Account<'info, ConvictionVoter>,
+
+    /// Left out once the candidate is closed, which the creator can only do
+    /// after the poll is finalized.
+    #[account(
+        mut,
+        seeds = [
+            poll_id.to_le_bytes().as_ref(),
+            question_index.to_le_bytes().as_ref(),
+            lock.candidate_name.as_bytes(),
+        ],
+        bump = candidate.bump
+    )]
+    pub candidate: Option<Account<'info, Candidate>>,
+
+    #[account(
+        address = poll.stake_mint,
+        mint::token_program = token_program,
+    )]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Returns locked tokens at any time. Unlocking before the poll ends also
+/// withdraws the lock's conviction; afterwards the result is already fixed.
+/// Once the poll itself is closed, `reclaim_lock` returns the tokens instead.
+pub fn unlock_tokens(ctx: Context<UnlockTokens>, poll_id: u64, question_index: u8) -> Result<()> {
+    let poll = &mut ctx.accounts.poll;
+    let now = Clock::get()?.unix_timestamp;
+    let lock = &mut ctx.accounts.lock;
+
+    // Once the poll has ended the clock stops at `poll_end`, so the
+    // candidate's final weight is unaffected by tokens leaving.
+    let at = poll.conviction_clock(now);
+    lock.accrue_conviction(at);
+    let uncounted = (now.max(0) as u64) <= poll.poll_end;
+    match ctx.accounts.candidate.as_mut() {
+        Some(candidate) => {
+            candidate.accrue_conviction(at);
+            candidate.conviction_amount -= lock.amount;
+            if uncounted {
+                candidate.conviction_score -= lock.score;
+            }
+        }
+        None => require!(poll.outcome != PollOutcome::Pending, VotingError::PollNotFinalized),
+    }
+    let conviction_voter = &mut ctx.accounts.conviction_voter;
+    conviction_voter.active_locks -= 1;
+    if uncounted && conviction_voter.active_locks == 0 {
+        poll.total_votes -= 1;
+    }
+
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, &poll_id.to_le_bytes(), &[ctx.bumps.vault]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.voter_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        lock.amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    emit!(TokensUnlocked {
+        poll_id,
+        voter: lock.voter,
+        question_index,
+        candidate: lock.candidate_name.clone(),
+        amount: lock.amount,
+        uncounted,
+    });
+
+    if ctx.accounts.conviction_voter.active_locks == 0 {
+        ctx.accounts.conviction_voter.close(ctx.accounts.signer.to_account_info())?;
+    }
+    Ok(())
+}
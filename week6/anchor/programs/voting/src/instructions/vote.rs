@@ -0,0 +1,135 @@
+use crate::constants::RECEIPT_SEED;
+use crate::errors::VotingError;
+use crate::events::VoteCast;
+use crate::state::{Candidate, Delegation, DelegationScope, Poll, VoteReceipt, VotingMode};
+use crate::utils::{create_pda_account, normalize_candidate_name};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(choices: Vec<String>, poll_id: u64)]
+pub struct Vote<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll.mode == VotingMode::Standard @ VotingError::WrongVotingMode,
+    )]
+    pub poll: Account<'info, Poll>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + VoteReceipt::INIT_SPACE,
+        seeds = [RECEIPT_SEED, poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn vote<'info>(ctx: Context<'_, '_, 'info, 'info, Vote<'info>>,
+                   choices: Vec<String>,
+                   poll_id: u64) -> Result<()> {
+    ctx.accounts.poll.require_open(Clock::get()?.unix_timestamp)?;
+
+    // A receipt that already names a voter means the signer voted, or was
+    // counted through someone they delegated to.
+    require_keys_eq!(ctx.accounts.receipt.voter, Pubkey::default(), VotingError::AlreadyVoted);
+
+    let choices: Vec<String> = choices.iter().map(|choice| normalize_candidate_name(choice)).collect();
+    require_eq!(choices.len(), ctx.accounts.poll.questions.len(), VotingError::IncompleteBallot);
+    require_gte!(ctx.remaining_accounts.len(), choices.len(), VotingError::IncompleteBallot);
+    let (candidate_infos, delegation_infos) = ctx.remaining_accounts.split_at(choices.len());
+
+    let delegations = delegation_infos.chunks_exact(2);
+    require!(delegations.remainder().is_empty(), VotingError::InvalidDelegationAccounts);
+
+    let mut delegators = Vec::new();
+    for pair in delegations {
+        delegators.push(count_delegated_vote(&ctx, &pair[0], &pair[1], poll_id)?);
+    }
+    let weight = 1 + delegators.len() as u64;
+
+    for (question_index, (choice, info)) in choices.iter().zip(candidate_infos).enumerate() {
+        let mut candidate = Account::<Candidate>::try_from(info)?;
+        candidate.verify_address(info.key, poll_id, ctx.program_id)?;
+        require!(
+            candidate.question_index as usize == question_index && candidate.candidate_name == *choice,
+            VotingError::InvalidCandidate
+        );
+        candidate.candidate_votes += weight;
+        candidate.exit(ctx.program_id)?;
+    }
+    ctx.accounts.poll.total_votes += weight;
+    ctx.accounts.poll.open_receipts += 1 + delegators.len() as u64;
+
+    let voter = ctx.accounts.signer.key();
+    ctx.accounts.receipt.set_inner(VoteReceipt {
+        poll_id,
+        voter,
+        choices: choices.clone(),
+        weight,
+        delegate: None,
+        bump: ctx.bumps.receipt,
+    });
+
+    emit!(VoteCast {
+        poll_id,
+        voter,
+        choices,
+        weight,
+        delegators,
+    });
+    Ok(())
+}
+
+/// Validates a delegation held by the signer and marks its delegator as voted
+/// by creating their receipt, so they can't also vote directly. Returns the delegator.
+fn count_delegated_vote<'info>(ctx: &Context<'_, '_, 'info, 'info, Vote<'info>>,
+                               delegation_info: &'info AccountInfo<'info>,
+                               receipt_info: &'info AccountInfo<'info>,
+                               poll_id: u64) -> Result<Pubkey> {
+    let mut delegation = Account::<Delegation>::try_from(delegation_info)?;
+    require_keys_eq!(delegation.delegate, ctx.accounts.signer.key(), VotingError::DelegateMismatch);
+    require!(delegation.scope.covers(&ctx.accounts.poll), VotingError::DelegationScopeMismatch);
+
+    let delegator = delegation.delegator;
+    let poll_seed = poll_id.to_le_bytes();
+    let (receipt_key, bump) = Pubkey::find_program_address(
+        &[RECEIPT_SEED, poll_seed.as_ref(), delegator.as_ref()],
+        ctx.program_id,
+    );
+    require_keys_eq!(receipt_info.key(), receipt_key, VotingError::InvalidReceipt);
+    require!(receipt_info.data_is_empty(), VotingError::AlreadyVoted);
+
+    let space = 8 + VoteReceipt::INIT_SPACE;
+    let receipt_seeds: &[&[u8]] = &[RECEIPT_SEED, poll_seed.as_ref(), delegator.as_ref(), &[bump]];
+    create_pda_account(
+        &ctx.accounts.signer.to_account_info(),
+        receipt_info,
+        &ctx.accounts.system_program.to_account_info(),
+        space,
+        ctx.program_id,
+        receipt_seeds,
+    )?;
+
+    let receipt = VoteReceipt {
+        poll_id,
+        voter: delegator,
+        choices: Vec::new(),
+        weight: 0,
+        delegate: Some(ctx.accounts.signer.key()),
+        bump,
+    };
+    receipt.try_serialize(&mut &mut receipt_info.try_borrow_mut_data()?[..])?;
+
+    if let DelegationScope::Poll(_) = delegation.scope {
+        delegation.used = true;
+        delegation.exit(ctx.program_id)?;
+    }
+    Ok(delegator)
+}
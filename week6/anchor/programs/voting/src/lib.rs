@@ -1,6 +1,15 @@
 #![allow(clippy::result_large_err)]
 
 use anchor_lang::prelude::*;
+use instructions::*;
+use state::{DelegationScope, PollOutcome, ProposalInstruction};
+
+pub mod constants;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod state;
+pub mod utils;
 
 declare_id!("6RMzzoy8iRv9a6ATQbxva3p5GCLFtBukjVN195aBNmQ8");
 
@@ -8,116 +17,126 @@ declare_id!("6RMzzoy8iRv9a6ATQbxva3p5GCLFtBukjVN195aBNmQ8");
 pub mod voting {
     use super::*;
 
-    pub fn initialize_poll(ctx: Context<InitializePoll>, 
+    pub fn initialize_poll(ctx: Context<InitializePoll>,
                             poll_id: u64,
                             description: String,
                             poll_start: u64,
-                            poll_end: u64) -> Result<()> {
-        let poll = &mut ctx.accounts.poll;
-        poll.poll_id = poll_id;
-        poll.description = description;
-        poll.poll_start = poll_start; 
-        poll.poll_end = poll_end;
-        poll.candidate_amount = 0;
-        Ok(())
+                            poll_end: u64,
+                            quorum: u64,
+                            threshold_bps: u16) -> Result<()> {
+        instructions::initialize_poll(ctx, poll_id, description, poll_start, poll_end, quorum, threshold_bps)
     }
 
     pub fn initialize_candidate(ctx: Context<InitializeCandidate>,
                                 candidate_name: String,
-                                _poll_id: u64) -> Result<()> {
-        let candidate = &mut ctx.accounts.candidate;
-        let poll = &mut ctx.accounts.poll;
-        poll.candidate_amount += 1;
-        candidate.candidate_name = candidate_name;
-        candidate.candidate_votes = 0;
-        Ok(())
-    }  
-
-    pub fn vote(ctx: Context<Vote>, _candidate_name: String, _poll_id: u64) -> Result<()> {
-        let candidate = &mut ctx.accounts.candidate;
-        candidate.candidate_votes += 1;
-        msg!("Voted for candidate: {}", candidate.candidate_name);
-        msg!("Votes: {}", candidate.candidate_votes);
-        Ok(())
+                                poll_id: u64,
+                                question_index: u8) -> Result<()> {
+        instructions::initialize_candidate(ctx, candidate_name, poll_id, question_index)
     }
-}
 
-#[derive(Accounts)]
-#[instruction(candidate_name: String, poll_id: u64)]
-pub struct Vote<'info> {
-    pub signer: Signer<'info>,
-
-    #[account(
-        seeds = [poll_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub poll: Account<'info, Poll>,
-
-    #[account(
-        mut,
-        seeds = [poll_id.to_le_bytes().as_ref(), candidate_name.as_bytes()],
-        bump
-    )]
-    pub candidate: Account<'info, Candidate>,
-}
+    pub fn add_question(ctx: Context<AddQuestion>, poll_id: u64, prompt: String) -> Result<()> {
+        instructions::add_question(ctx, poll_id, prompt)
+    }
 
-#[derive(Accounts)]
-#[instruction(candidate_name: String, poll_id: u64)]
-pub struct InitializeCandidate<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [poll_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub poll: Account<'info, Poll>,
-
-    #[account(
-        init,
-        payer = signer,
-        space = 8 + Candidate::INIT_SPACE,
-        seeds = [poll_id.to_le_bytes().as_ref(), candidate_name.as_bytes()],
-        bump
-    )]
-    pub candidate: Account<'info, Candidate>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// `choices` holds one candidate name per question. Remaining accounts are
+    /// those candidates in question order, followed by `(delegation,
+    /// delegator_receipt)` pairs for every voter whose weight the signer is
+    /// casting on top of their own vote.
+    pub fn vote<'info>(ctx: Context<'_, '_, 'info, 'info, Vote<'info>>,
+                       choices: Vec<String>,
+                       poll_id: u64) -> Result<()> {
+        instructions::vote(ctx, choices, poll_id)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Candidate {
-    #[max_len(32)]
-    pub candidate_name: String,
-    pub candidate_votes: u64,
-}
+    pub fn change_vote(ctx: Context<ChangeVote>,
+                       question_index: u8,
+                       new_candidate_name: String,
+                       poll_id: u64) -> Result<()> {
+        instructions::change_vote(ctx, question_index, new_candidate_name, poll_id)
+    }
 
-#[derive(Accounts)]
-#[instruction(poll_id: u64)]
-pub struct InitializePoll<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    #[account(
-        init,
-        payer = signer,
-        space = 8 + Poll::INIT_SPACE,
-        seeds = [poll_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub poll: Account<'info, Poll>,
-
-    pub system_program: Program<'info, System>,
-}
+    pub fn retract_vote<'info>(ctx: Context<'_, '_, 'info, 'info, RetractVote<'info>>,
+                               poll_id: u64) -> Result<()> {
+        instructions::retract_vote(ctx, poll_id)
+    }
+
+    /// Remaining accounts are every `Candidate` of the poll.
+    pub fn finalize_poll<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizePoll<'info>>,
+                                poll_id: u64) -> Result<PollOutcome> {
+        instructions::finalize_poll(ctx, poll_id)
+    }
+
+    pub fn close_candidate(ctx: Context<CloseCandidate>,
+                           poll_id: u64,
+                           question_index: u8,
+                           candidate_name: String) -> Result<()> {
+        instructions::close_candidate(ctx, poll_id, question_index, candidate_name)
+    }
+
+    pub fn close_receipt(ctx: Context<CloseReceipt>, poll_id: u64, voter: Pubkey) -> Result<()> {
+        instructions::close_receipt(ctx, poll_id, voter)
+    }
+
+    pub fn close_proposal(ctx: Context<CloseProposal>, poll_id: u64) -> Result<()> {
+        instructions::close_proposal(ctx, poll_id)
+    }
+
+    pub fn close_poll(ctx: Context<ClosePoll>, poll_id: u64) -> Result<()> {
+        instructions::close_poll(ctx, poll_id)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>,
+                           poll_id: u64,
+                           instructions: Vec<ProposalInstruction>,
+                           timelock: u64) -> Result<()> {
+        instructions::create_proposal(ctx, poll_id, instructions, timelock)
+    }
+
+    /// Remaining accounts are every account and program the proposal's
+    /// instructions reference, except the treasury.
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+                                   poll_id: u64) -> Result<()> {
+        instructions::execute_proposal(ctx, poll_id)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Poll {
-    pub poll_id: u64,
-    #[max_len(280)]
-    pub description: String,
-    pub poll_start: u64,
-    pub poll_end: u64,
-    pub candidate_amount: u64,
-}
\ No newline at end of file
+    pub fn enable_conviction(ctx: Context<EnableConviction>, poll_id: u64) -> Result<()> {
+        instructions::enable_conviction(ctx, poll_id)
+    }
+
+    pub fn lock_tokens(ctx: Context<LockTokens>,
+                       poll_id: u64,
+                       question_index: u8,
+                       candidate_name: String,
+                       amount: u64) -> Result<()> {
+        instructions::lock_tokens(ctx, poll_id, question_index, candidate_name, amount)
+    }
+
+    /// Moves a lock to another candidate on the same question, keeping
+    /// `MOVE_CONVICTION_KEPT_BPS` of its conviction.
+    pub fn move_lock(ctx: Context<MoveLock>,
+                     poll_id: u64,
+                     question_index: u8,
+                     new_candidate_name: String) -> Result<()> {
+        instructions::move_lock(ctx, poll_id, question_index, new_candidate_name)
+    }
+
+    pub fn unlock_tokens(ctx: Context<UnlockTokens>, poll_id: u64, question_index: u8) -> Result<()> {
+        instructions::unlock_tokens(ctx, poll_id, question_index)
+    }
+
+    /// Returns a lock left behind when its poll was closed.
+    pub fn reclaim_lock(ctx: Context<ReclaimLock>, poll_id: u64, question_index: u8) -> Result<()> {
+        instructions::reclaim_lock(ctx, poll_id, question_index)
+    }
+
+    pub fn delegate_vote(ctx: Context<DelegateVote>,
+                         scope: DelegationScope,
+                         delegate: Pubkey) -> Result<()> {
+        instructions::delegate_vote(ctx, scope, delegate)
+    }
+
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>,
+                             scope: DelegationScope) -> Result<()> {
+        instructions::revoke_delegation(ctx, scope)
+    }
+}
@@ -0,0 +1,521 @@
+use crate::constants::{
+    CLOSE_GRACE_PERIOD, MAX_CANDIDATE_NAME_LEN, MAX_DESCRIPTION_LEN, MAX_QUESTIONS, MAX_THRESHOLD_BPS,
+    MOVE_CONVICTION_KEPT_BPS,
+};
+use crate::errors::VotingError;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Poll {
+    pub poll_id: u64,
+    #[max_len(MAX_DESCRIPTION_LEN)]
+    pub description: String,
+    pub poll_start: u64,
+    pub poll_end: u64,
+    pub candidate_amount: u64,
+    pub creator: Pubkey,
+    /// Minimum total votes for the poll to produce a result.
+    pub quorum: u64,
+    /// Share of the total votes, in basis points, the leading candidate needs to pass.
+    pub threshold_bps: u16,
+    /// Weight of every ballot cast. In conviction mode, the number of voters
+    /// holding at least one lock.
+    pub total_votes: u64,
+    pub outcome: PollOutcome,
+    pub finalized_at: u64,
+    /// Every ballot answers all questions, in order. The first question is the
+    /// poll description itself.
+    #[max_len(MAX_QUESTIONS)]
+    pub questions: Vec<Question>,
+    pub mode: VotingMode,
+    /// Mint locked in conviction mode; unused for standard polls.
+    pub stake_mint: Pubkey,
+    /// Vote receipts still holding rent. They have to be closed before the
+    /// poll, since closing a receipt needs the poll account.
+    pub open_receipts: u64,
+    /// Set while the poll has a `Proposal` account.
+    pub has_proposal: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VotingMode {
+    /// One ballot per voter, weighted by delegations.
+    Standard,
+    /// Voters lock tokens on a candidate; weight is tokens multiplied by the
+    /// seconds they stay locked within the poll window.
+    Conviction,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Question {
+    #[max_len(MAX_DESCRIPTION_LEN)]
+    pub prompt: String,
+    pub candidate_amount: u64,
+    #[max_len(MAX_CANDIDATE_NAME_LEN)]
+    pub winner: Option<String>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum PollOutcome {
+    Pending,
+    Passed,
+    Failed,
+    NoQuorum,
+}
+
+impl Poll {
+    pub fn require_open(&self, now: i64) -> Result<()> {
+        let now = now.max(0) as u64;
+        require_gte!(now, self.poll_start, VotingError::PollNotStarted);
+        require_gte!(self.poll_end, now, VotingError::PollEnded);
+        Ok(())
+    }
+
+    /// Accounts of a poll can be closed once it is finalized and the grace
+    /// period after its end has passed.
+    pub fn require_closable(&self, now: i64) -> Result<()> {
+        require!(self.outcome != PollOutcome::Pending, VotingError::PollNotFinalized);
+        let now = now.max(0) as u64;
+        let closable_at = self.poll_end.saturating_add(CLOSE_GRACE_PERIOD);
+        require_gt!(now, closable_at, VotingError::GracePeriodActive);
+        Ok(())
+    }
+
+    /// Decides the outcome from every candidate's weight and records each
+    /// question's winner. The poll passes only if every question does.
+    pub fn tally(&mut self, candidates: &[&Candidate]) -> PollOutcome {
+        for question in self.questions.iter_mut() {
+            question.winner = None;
+        }
+        if self.total_votes == 0 || self.total_votes < self.quorum {
+            return PollOutcome::NoQuorum;
+        }
+
+        let (mode, poll_end, threshold_bps) = (self.mode, self.poll_end, self.threshold_bps);
+        let mut passed = true;
+        for (index, question) in self.questions.iter_mut().enumerate() {
+            let weights: Vec<(&str, u128)> = candidates
+                .iter()
+                .filter(|candidate| candidate.question_index as usize == index)
+                .map(|candidate| (candidate.candidate_name.as_str(), candidate.weight(mode, poll_end)))
+                .collect();
+            question.winner = passing_candidate(&weights, threshold_bps);
+            passed &= question.winner.is_some();
+        }
+
+        if passed {
+            PollOutcome::Passed
+        } else {
+            PollOutcome::Failed
+        }
+    }
+
+    /// Conviction only accrues inside the poll window.
+    pub fn conviction_clock(&self, now: i64) -> u64 {
+        (now.max(0) as u64).max(self.poll_start).min(self.poll_end)
+    }
+}
+
+/// The leading candidate, if its share of the question's total weight reaches
+/// the threshold. A tie for first place never passes, whatever the threshold.
+fn passing_candidate(weights: &[(&str, u128)], threshold_bps: u16) -> Option<String> {
+    let mut leader: Option<(&str, u128)> = None;
+    let mut tied = false;
+    for &(name, weight) in weights {
+        match leader {
+            Some((_, current)) if weight < current => {}
+            Some((_, current)) if weight == current => tied = true,
+            _ => {
+                leader = Some((name, weight));
+                tied = false;
+            }
+        }
+    }
+
+    let (name, weight) = leader.filter(|_| !tied)?;
+    let total: u128 = weights.iter().map(|(_, weight)| weight).sum();
+    let share = weight.saturating_mul(MAX_THRESHOLD_BPS as u128);
+    let required = total.saturating_mul(threshold_bps as u128);
+    (total > 0 && share >= required).then(|| name.to_string())
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Candidate {
+    #[max_len(MAX_CANDIDATE_NAME_LEN)]
+    pub candidate_name: String,
+    pub candidate_votes: u64,
+    pub question_index: u8,
+    /// Receives the rent back when the candidate is closed.
+    pub payer: Pubkey,
+    pub bump: u8,
+    /// Tokens currently locked on this candidate in conviction mode.
+    pub conviction_amount: u64,
+    /// Token-seconds accrued up to `conviction_updated_at`.
+    pub conviction_score: u128,
+    pub conviction_updated_at: u64,
+}
+
+impl Candidate {
+    pub fn weight(&self, mode: VotingMode, poll_end: u64) -> u128 {
+        match mode {
+            VotingMode::Standard => self.candidate_votes as u128,
+            VotingMode::Conviction => self.conviction_at(poll_end),
+        }
+    }
+
+    pub fn conviction_at(&self, at: u64) -> u128 {
+        accrue(self.conviction_score, self.conviction_amount, self.conviction_updated_at, at)
+    }
+
+    /// Brings the accumulator up to `at`, which must come from `Poll::conviction_clock`.
+    pub fn accrue_conviction(&mut self, at: u64) {
+        self.conviction_score = self.conviction_at(at);
+        self.conviction_updated_at = self.conviction_updated_at.max(at);
+    }
+
+    /// Checks that `key` is this candidate's address for the given poll.
+    pub fn verify_address(&self, key: &Pubkey, poll_id: u64, program_id: &Pubkey) -> Result<()> {
+        let address = Pubkey::create_program_address(
+            &[
+                poll_id.to_le_bytes().as_ref(),
+                self.question_index.to_le_bytes().as_ref(),
+                self.candidate_name.as_bytes(),
+                &[self.bump],
+            ],
+            program_id,
+        )
+        .map_err(|_| VotingError::InvalidCandidate)?;
+        require_keys_eq!(*key, address, VotingError::InvalidCandidate);
+        Ok(())
+    }
+}
+
+/// Marks that `voter` has been counted in a poll, either directly or through a delegate.
+#[account]
+#[derive(InitSpace)]
+pub struct VoteReceipt {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    /// Chosen candidate for each question, in order. Empty for receipts
+    /// created on behalf of a delegator: the delegate's receipt holds the choices.
+    #[max_len(MAX_QUESTIONS, MAX_CANDIDATE_NAME_LEN)]
+    pub choices: Vec<String>,
+    /// Votes added to each chosen candidate by this receipt: the voter's own vote plus
+    /// delegated weight. Zero for receipts created on behalf of a delegator.
+    pub weight: u64,
+    /// Set when the vote was cast by a delegate rather than the voter.
+    pub delegate: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl VoteReceipt {
+    /// Delegator receipts are paid for by the delegate who cast the vote.
+    pub fn payer(&self) -> Pubkey {
+        self.delegate.unwrap_or(self.voter)
+    }
+
+    /// Seed of the candidate chosen for `question_index`; empty if there is none.
+    pub fn choice_seed(&self, question_index: u8) -> &[u8] {
+        self.choices
+            .get(question_index as usize)
+            .map(String::as_bytes)
+            .unwrap_or_default()
+    }
+}
+
+/// Tokens a voter has locked on one question of a conviction poll.
+#[account]
+#[derive(InitSpace)]
+pub struct ConvictionLock {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub question_index: u8,
+    #[max_len(MAX_CANDIDATE_NAME_LEN)]
+    pub candidate_name: String,
+    pub amount: u64,
+    /// The voter's share of the candidate's accumulator, as of `updated_at`.
+    pub score: u128,
+    pub updated_at: u64,
+    pub bump: u8,
+}
+
+impl ConvictionLock {
+    pub fn accrue_conviction(&mut self, at: u64) {
+        self.score = accrue(self.score, self.amount, self.updated_at, at);
+        self.updated_at = self.updated_at.max(at);
+    }
+
+    /// Moves the lock from `from` to `to` at `at`, which must come from
+    /// `Poll::conviction_clock`. Only `MOVE_CONVICTION_KEPT_BPS` of the
+    /// conviction built up so far comes along; the new candidate's weight
+    /// then grows from there.
+    pub fn move_to(&mut self, from: &mut Candidate, to: &mut Candidate, at: u64) {
+        from.accrue_conviction(at);
+        to.accrue_conviction(at);
+        self.accrue_conviction(at);
+
+        let kept = self.score * MOVE_CONVICTION_KEPT_BPS as u128 / MAX_THRESHOLD_BPS as u128;
+        from.conviction_amount -= self.amount;
+        from.conviction_score -= self.score;
+        to.conviction_amount += self.amount;
+        to.conviction_score += kept;
+        self.score = kept;
+    }
+}
+
+/// Counts a voter's active locks on a conviction poll, so the voter adds one
+/// to the poll's quorum count however many questions they lock on.
+#[account]
+#[derive(InitSpace)]
+pub struct ConvictionVoter {
+    pub poll_id: u64,
+    pub voter: Pubkey,
+    pub active_locks: u8,
+    pub bump: u8,
+}
+
+fn accrue(score: u128, amount: u64, updated_at: u64, at: u64) -> u128 {
+    let elapsed = at.saturating_sub(updated_at);
+    score.saturating_add(amount as u128 * elapsed as u128)
+}
+
+/// What is left of a poll after its accounts are closed. It stays at its
+/// address for good, which retires the poll id: `initialize_poll` refuses an id
+/// that already has a summary.
+#[account]
+#[derive(InitSpace)]
+pub struct PollSummary {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    pub outcome: PollOutcome,
+    pub total_votes: u64,
+    pub finalized_at: u64,
+    #[max_len(MAX_QUESTIONS, MAX_CANDIDATE_NAME_LEN)]
+    pub winners: Vec<Option<String>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum DelegationScope {
+    /// A single poll, by id.
+    Poll(u64),
+    /// Every poll created by this wallet.
+    Creator(Pubkey),
+}
+
+impl DelegationScope {
+    pub fn seed(&self) -> Vec<u8> {
+        match self {
+            DelegationScope::Poll(poll_id) => poll_id.to_le_bytes().to_vec(),
+            DelegationScope::Creator(creator) => creator.to_bytes().to_vec(),
+        }
+    }
+
+    pub fn covers(&self, poll: &Poll) -> bool {
+        match self {
+            DelegationScope::Poll(poll_id) => *poll_id == poll.poll_id,
+            DelegationScope::Creator(creator) => *creator == poll.creator,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub scope: DelegationScope,
+    /// Only tracked for poll-scoped delegations, which can't be revoked once used.
+    pub used: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct ProposalInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl ProposalInstruction {
+    pub fn space(&self) -> usize {
+        32 + 4 + self.accounts.len() * (32 + 1 + 1) + 4 + self.data.len()
+    }
+}
+
+/// Instructions run by the poll's treasury once the poll with the same id passes.
+#[account]
+pub struct Proposal {
+    pub poll_id: u64,
+    pub instructions: Vec<ProposalInstruction>,
+    /// Seconds that must elapse between finalization and execution.
+    pub timelock: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub fn space(instructions: &[ProposalInstruction]) -> usize {
+        8 + 4 + instructions.iter().map(ProposalInstruction::space).sum::<usize>() + 8 + 1 + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &str) -> Candidate {
+        Candidate {
+            candidate_name: name.to_string(),
+            candidate_votes: 0,
+            question_index: 0,
+            payer: Pubkey::default(),
+            bump: 0,
+            conviction_amount: 0,
+            conviction_score: 0,
+            conviction_updated_at: 0,
+        }
+    }
+
+    fn poll(mode: VotingMode, total_votes: u64) -> Poll {
+        Poll {
+            poll_id: 1,
+            description: "Which team?".to_string(),
+            poll_start: 100,
+            poll_end: 200,
+            candidate_amount: 2,
+            creator: Pubkey::default(),
+            quorum: 2,
+            threshold_bps: 6_000,
+            total_votes,
+            outcome: PollOutcome::Pending,
+            finalized_at: 0,
+            questions: vec![Question {
+                prompt: "Which team?".to_string(),
+                candidate_amount: 2,
+                winner: None,
+            }],
+            mode,
+            stake_mint: Pubkey::default(),
+            open_receipts: 0,
+            has_proposal: false,
+        }
+    }
+
+    fn voted(name: &str, votes: u64) -> Candidate {
+        Candidate {
+            candidate_votes: votes,
+            ..candidate(name)
+        }
+    }
+
+    fn lock_on(candidate: &mut Candidate, amount: u64, at: u64) -> ConvictionLock {
+        candidate.accrue_conviction(at);
+        candidate.conviction_amount += amount;
+        ConvictionLock {
+            poll_id: 1,
+            voter: Pubkey::new_unique(),
+            question_index: 0,
+            candidate_name: candidate.candidate_name.clone(),
+            amount,
+            score: 0,
+            updated_at: at,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn moving_a_lock_keeps_part_of_its_conviction() {
+        let mut yes = candidate("yes");
+        let mut no = candidate("no");
+        let mut lock = lock_on(&mut yes, 100, 0);
+
+        lock.move_to(&mut yes, &mut no, 10);
+
+        // 100 tokens for 10 seconds, half of which survives the move.
+        assert_eq!(lock.score, 500);
+        assert_eq!(yes.conviction_at(10), 0);
+        assert_eq!(yes.conviction_amount, 0);
+        assert_eq!(no.conviction_at(10), 500);
+        assert_eq!(no.conviction_at(20), 1_500);
+    }
+
+    #[test]
+    fn moving_back_and_forth_does_not_build_conviction() {
+        let mut yes = candidate("yes");
+        let mut no = candidate("no");
+        let mut lock = lock_on(&mut yes, 100, 0);
+
+        lock.move_to(&mut yes, &mut no, 10);
+        lock.move_to(&mut no, &mut yes, 10);
+
+        assert_eq!(lock.score, 250);
+        assert_eq!(yes.conviction_at(10), 250);
+        assert_eq!(no.conviction_at(10), 0);
+    }
+
+    #[test]
+    fn moving_leaves_other_locks_on_the_old_candidate() {
+        let mut yes = candidate("yes");
+        let mut no = candidate("no");
+        let mut stays = lock_on(&mut yes, 50, 0);
+        let mut moves = lock_on(&mut yes, 100, 0);
+
+        moves.move_to(&mut yes, &mut no, 10);
+        stays.accrue_conviction(20);
+
+        assert_eq!(yes.conviction_amount, 50);
+        assert_eq!(yes.conviction_at(20), stays.score);
+    }
+
+    #[test]
+    fn tally_needs_quorum() {
+        let mut poll = poll(VotingMode::Standard, 1);
+        let (red, blue) = (voted("red", 1), voted("blue", 0));
+        assert_eq!(poll.tally(&[&red, &blue]), PollOutcome::NoQuorum);
+        assert_eq!(poll.questions[0].winner, None);
+    }
+
+    #[test]
+    fn tally_needs_the_threshold() {
+        let mut poll = poll(VotingMode::Standard, 5);
+        // 3 of 5 votes is exactly the 60% threshold.
+        let (red, blue) = (voted("red", 3), voted("blue", 2));
+        assert_eq!(poll.tally(&[&red, &blue]), PollOutcome::Passed);
+        assert_eq!(poll.questions[0].winner, Some("red".to_string()));
+
+        poll.threshold_bps = 7_000;
+        assert_eq!(poll.tally(&[&red, &blue]), PollOutcome::Failed);
+        assert_eq!(poll.questions[0].winner, None);
+    }
+
+    #[test]
+    fn tally_never_passes_a_tie() {
+        let mut poll = poll(VotingMode::Standard, 4);
+        poll.threshold_bps = 0;
+        let (red, blue) = (voted("red", 2), voted("blue", 2));
+        assert_eq!(poll.tally(&[&red, &blue]), PollOutcome::Failed);
+    }
+
+    #[test]
+    fn conviction_tally_weighs_tokens_by_time_locked() {
+        let mut poll = poll(VotingMode::Conviction, 2);
+        poll.threshold_bps = 5_000;
+        let mut red = candidate("red");
+        let mut blue = candidate("blue");
+        // Three times the tokens on blue, locked for a quarter of the window.
+        lock_on(&mut red, 100, 100);
+        lock_on(&mut blue, 300, 175);
+
+        assert_eq!(poll.tally(&[&red, &blue]), PollOutcome::Passed);
+        assert_eq!(poll.questions[0].winner, Some("red".to_string()));
+    }
+}
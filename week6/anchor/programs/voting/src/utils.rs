@@ -0,0 +1,67 @@
+use crate::constants::{MAX_CANDIDATE_NAME_LEN, MAX_DESCRIPTION_LEN};
+use crate::errors::VotingError;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer};
+
+/// Candidates are keyed by their trimmed, lowercased name, so "Alice" and
+/// "alice " are the same candidate.
+pub fn normalize_candidate_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// PDA seed for a candidate name. Clamped to the seed length limit so an
+/// over-long name reaches `validate_candidate_name` instead of aborting
+/// address derivation.
+pub fn candidate_seed(name: &str) -> Vec<u8> {
+    let mut seed = normalize_candidate_name(name).into_bytes();
+    seed.truncate(MAX_CANDIDATE_NAME_LEN);
+    seed
+}
+
+/// Returns the normalized name.
+pub fn validate_candidate_name(name: &str) -> Result<String> {
+    let name = normalize_candidate_name(name);
+    require!(!name.is_empty(), VotingError::CandidateNameEmpty);
+    require_gte!(MAX_CANDIDATE_NAME_LEN, name.len(), VotingError::CandidateNameTooLong);
+    Ok(name)
+}
+
+/// Used for poll descriptions and question prompts.
+pub fn validate_description(description: &str) -> Result<()> {
+    require!(!description.trim().is_empty(), VotingError::DescriptionEmpty);
+    require_gte!(MAX_DESCRIPTION_LEN, description.len(), VotingError::DescriptionTooLong);
+    Ok(())
+}
+
+/// Creates a program-owned account at the PDA signed for by `seeds`, the way
+/// Anchor's `init` does. Anyone can send lamports to a PDA before it exists,
+/// which makes `create_account` fail; in that case the balance is only topped
+/// up to rent exemption before the account is allocated and assigned.
+pub fn create_pda_account<'info>(payer: &AccountInfo<'info>,
+                                 account: &AccountInfo<'info>,
+                                 system_program: &AccountInfo<'info>,
+                                 space: usize,
+                                 owner: &Pubkey,
+                                 seeds: &[&[u8]]) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    if current == 0 {
+        let accounts = CreateAccount { from: payer.clone(), to: account.clone() };
+        return create_account(
+            CpiContext::new_with_signer(system_program.clone(), accounts, &[seeds]),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = rent.saturating_sub(current);
+    if top_up > 0 {
+        let accounts = Transfer { from: payer.clone(), to: account.clone() };
+        transfer(CpiContext::new(system_program.clone(), accounts), top_up)?;
+    }
+    let accounts = Allocate { account_to_allocate: account.clone() };
+    allocate(CpiContext::new_with_signer(system_program.clone(), accounts, &[seeds]), space as u64)?;
+    let accounts = Assign { account_to_assign: account.clone() };
+    assign(CpiContext::new_with_signer(system_program.clone(), accounts, &[seeds]), owner)
+}
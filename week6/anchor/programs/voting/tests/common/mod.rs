@@ -0,0 +1,134 @@
+//! Runs the built voting program under LiteSVM, so every instruction goes
+//! through the real runtime: signer, writable, owner and rent checks included.
+//!
+//! The program is loaded from `$SBF_OUT_DIR/voting.so`, which `cargo test-sbf`
+//! sets, or from `target/deploy/voting.so` after `anchor build`. Plain
+//! `cargo test` runs these tests too, and every one of them fails until the
+//! program has been built.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use std::path::PathBuf;
+
+fn program_path() -> PathBuf {
+    let out_dir = match std::env::var_os("SBF_OUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"),
+    };
+    out_dir.join("voting.so")
+}
+
+pub struct Svm {
+    svm: LiteSVM,
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        let path = program_path();
+        svm.add_program_from_file(voting::ID, &path)
+            .unwrap_or_else(|err| panic!("loading {}: {err}; run `anchor build` or `cargo test-sbf`", path.display()));
+        Self { svm }
+    }
+
+    /// Sets the clock seen by every following transaction.
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.svm.get_sysvar();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn funded_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.airdrop(&wallet.pubkey(), 10_000_000_000);
+        wallet
+    }
+
+    /// Sends lamports to any address, e.g. a PDA that doesn't exist yet.
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        self.svm.airdrop(key, lamports).unwrap();
+    }
+
+    /// An SPL token mint with no authority, for conviction polls.
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: u64::MAX,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        let key = Pubkey::new_unique();
+        self.set_token_program_account(key, data);
+        key
+    }
+
+    /// A token account of `owner` holding `amount` of `mint`.
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        let key = Pubkey::new_unique();
+        self.set_token_program_account(key, data);
+        key
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = self.get_account(key).unwrap_or_else(|| panic!("account {key} does not exist"));
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    fn set_token_program_account(&mut self, key: Pubkey, data: Vec<u8>) {
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(key, account).unwrap();
+    }
+
+    /// Accounts that were closed read as missing.
+    pub fn get_account(&self, key: &Pubkey) -> Option<Account> {
+        self.svm.get_account(key).filter(|account| account.lamports > 0)
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.get_account(key).unwrap_or_else(|| panic!("account {key} does not exist"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Sends `ix` in its own transaction, paid for by the first signer.
+    pub fn process(&mut self, ix: Instruction, signers: &[&Keypair]) -> std::result::Result<(), TransactionError> {
+        let message = Message::new(&[ix], Some(&signers[0].pubkey()));
+        let tx = Transaction::new(signers, message, self.svm.latest_blockhash());
+        let result = self.svm.send_transaction(tx).map(|_| ()).map_err(|failed| failed.err);
+        // A fresh blockhash lets a test send the same instruction twice.
+        self.svm.expire_blockhash();
+        result
+    }
+}
+
+/// The error a failed single-instruction transaction reports for `code`.
+pub fn instruction_error(code: u32) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(code))
+}
@@ -0,0 +1,1035 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{instruction_error, Svm};
+use solana_keypair::Keypair;
+use solana_signer::Signer as _;
+use solana_transaction_error::TransactionError;
+use voting::constants::{
+    CLOSE_GRACE_PERIOD, DELEGATION_SEED, LOCK_SEED, PROPOSAL_SEED, RECEIPT_SEED, SUMMARY_SEED, TREASURY_SEED,
+    VAULT_SEED, VOTER_SEED,
+};
+use voting::errors::VotingError;
+use voting::state::{
+    Candidate, ConvictionLock, DelegationScope, Poll, PollOutcome, ProposalAccountMeta, ProposalInstruction, VoteReceipt,
+};
+
+type TxResult = std::result::Result<(), TransactionError>;
+
+const POLL_ID: u64 = 1;
+const POLL_START: u64 = 100;
+const POLL_END: u64 = 200;
+
+fn poll_address() -> Pubkey {
+    Pubkey::find_program_address(&[POLL_ID.to_le_bytes().as_ref()], &voting::ID).0
+}
+
+fn summary_address() -> Pubkey {
+    Pubkey::find_program_address(&[SUMMARY_SEED, POLL_ID.to_le_bytes().as_ref()], &voting::ID).0
+}
+
+fn candidate_address(question_index: u8, name: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POLL_ID.to_le_bytes().as_ref(), question_index.to_le_bytes().as_ref(), name.as_bytes()],
+        &voting::ID,
+    )
+    .0
+}
+
+fn receipt_address(voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[RECEIPT_SEED, POLL_ID.to_le_bytes().as_ref(), voter.as_ref()], &voting::ID).0
+}
+
+fn delegation_address(delegator: &Pubkey, scope: &DelegationScope) -> Pubkey {
+    Pubkey::find_program_address(&[DELEGATION_SEED, delegator.as_ref(), scope.seed().as_ref()], &voting::ID).0
+}
+
+fn proposal_address() -> Pubkey {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, POLL_ID.to_le_bytes().as_ref()], &voting::ID).0
+}
+
+fn treasury_address() -> Pubkey {
+    Pubkey::find_program_address(&[TREASURY_SEED, poll_address().as_ref()], &voting::ID).0
+}
+
+fn vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED, POLL_ID.to_le_bytes().as_ref()], &voting::ID).0
+}
+
+/// Locks in these tests are all on the first question.
+fn lock_address(voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[LOCK_SEED, POLL_ID.to_le_bytes().as_ref(), voter.as_ref(), 0u8.to_le_bytes().as_ref()],
+        &voting::ID,
+    )
+    .0
+}
+
+fn conviction_voter_address(voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VOTER_SEED, POLL_ID.to_le_bytes().as_ref(), voter.as_ref()], &voting::ID).0
+}
+
+fn voting_error(error: VotingError) -> TransactionError {
+    instruction_error(error.into())
+}
+
+fn initialize_poll(svm: &mut Svm, creator: &Keypair) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::InitializePoll {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            summary: summary_address(),
+            system_program: System::id(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::InitializePoll {
+            poll_id: POLL_ID,
+            description: "What is your favorite type of peanut butter?".to_string(),
+            poll_start: POLL_START,
+            poll_end: POLL_END,
+            quorum: 1,
+            threshold_bps: 5000,
+        }
+        .data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+fn initialize_candidate(svm: &mut Svm, creator: &Keypair, question_index: u8, name: &str) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::InitializeCandidate {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            candidate: candidate_address(question_index, &name.trim().to_lowercase()),
+            system_program: System::id(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::InitializeCandidate {
+            candidate_name: name.to_string(),
+            poll_id: POLL_ID,
+            question_index,
+        }
+        .data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+fn add_question(svm: &mut Svm, signer: &Keypair, prompt: &str) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::AddQuestion {
+            signer: signer.pubkey(),
+            poll: poll_address(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::AddQuestion {
+            poll_id: POLL_ID,
+            prompt: prompt.to_string(),
+        }
+        .data(),
+    };
+    svm.process(ix, &[signer])
+}
+
+/// `delegations` are the delegators whose votes `voter` casts on top of their own.
+fn vote_instruction(voter: &Pubkey, choice: &str, delegations: &[Pubkey]) -> Instruction {
+    let mut accounts = voting::accounts::Vote {
+        signer: *voter,
+        poll: poll_address(),
+        receipt: receipt_address(voter),
+        system_program: System::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(candidate_address(0, choice), false));
+    for delegator in delegations {
+        let delegation = delegation_address(delegator, &DelegationScope::Poll(POLL_ID));
+        accounts.push(AccountMeta::new(delegation, false));
+        accounts.push(AccountMeta::new(receipt_address(delegator), false));
+    }
+
+    Instruction {
+        program_id: voting::ID,
+        accounts,
+        data: voting::instruction::Vote {
+            choices: vec![choice.to_string()],
+            poll_id: POLL_ID,
+        }
+        .data(),
+    }
+}
+
+fn vote(svm: &mut Svm, voter: &Keypair, choice: &str, delegations: &[Pubkey]) -> TxResult {
+    svm.process(vote_instruction(&voter.pubkey(), choice, delegations), &[voter])
+}
+
+/// `delegators` are those whose votes `voter` cast, and whose receipts close with theirs.
+fn retract_vote(svm: &mut Svm, voter: &Keypair, choice: &str, delegators: &[Pubkey]) -> TxResult {
+    let mut accounts = voting::accounts::RetractVote {
+        signer: voter.pubkey(),
+        poll: poll_address(),
+        receipt: receipt_address(&voter.pubkey()),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(candidate_address(0, choice), false));
+    for delegator in delegators {
+        accounts.push(AccountMeta::new(receipt_address(delegator), false));
+    }
+
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts,
+        data: voting::instruction::RetractVote { poll_id: POLL_ID }.data(),
+    };
+    svm.process(ix, &[voter])
+}
+
+fn delegate_vote(svm: &mut Svm, delegator: &Keypair, delegate: &Pubkey) {
+    let scope = DelegationScope::Poll(POLL_ID);
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::DelegateVote {
+            delegator: delegator.pubkey(),
+            delegation: delegation_address(&delegator.pubkey(), &scope),
+            system_program: System::id(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::DelegateVote {
+            scope,
+            delegate: *delegate,
+        }
+        .data(),
+    };
+    svm.process(ix, &[delegator]).unwrap();
+}
+
+fn finalize_poll(svm: &mut Svm, signer: &Keypair, candidates: &[&str]) -> TxResult {
+    let mut accounts = voting::accounts::FinalizePoll {
+        signer: signer.pubkey(),
+        poll: poll_address(),
+    }
+    .to_account_metas(None);
+    for name in candidates {
+        accounts.push(AccountMeta::new_readonly(candidate_address(0, name), false));
+    }
+
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts,
+        data: voting::instruction::FinalizePoll { poll_id: POLL_ID }.data(),
+    };
+    svm.process(ix, &[signer])
+}
+
+fn change_vote(svm: &mut Svm, voter: &Keypair, old_choice: &str, new_choice: &str) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::ChangeVote {
+            signer: voter.pubkey(),
+            poll: poll_address(),
+            receipt: receipt_address(&voter.pubkey()),
+            old_candidate: candidate_address(0, old_choice),
+            new_candidate: candidate_address(0, new_choice),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::ChangeVote {
+            question_index: 0,
+            new_candidate_name: new_choice.to_string(),
+            poll_id: POLL_ID,
+        }
+        .data(),
+    };
+    svm.process(ix, &[voter])
+}
+
+/// A proposal paying `lamports` from the poll's treasury to `recipient`.
+fn create_proposal(svm: &mut Svm, creator: &Keypair, recipient: &Pubkey, lamports: u64, timelock: u64) -> TxResult {
+    let transfer = solana_system_interface::instruction::transfer(&treasury_address(), recipient, lamports);
+    let instruction = ProposalInstruction {
+        program_id: transfer.program_id,
+        accounts: transfer
+            .accounts
+            .iter()
+            .map(|meta| ProposalAccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: transfer.data,
+    };
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::CreateProposal {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            proposal: proposal_address(),
+            system_program: System::id(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::CreateProposal {
+            poll_id: POLL_ID,
+            instructions: vec![instruction],
+            timelock,
+        }
+        .data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+fn execute_proposal(svm: &mut Svm, signer: &Keypair, recipient: &Pubkey) -> TxResult {
+    let mut accounts = voting::accounts::ExecuteProposal {
+        signer: signer.pubkey(),
+        poll: poll_address(),
+        proposal: proposal_address(),
+        treasury: treasury_address(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(*recipient, false));
+    accounts.push(AccountMeta::new_readonly(System::id(), false));
+
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts,
+        data: voting::instruction::ExecuteProposal { poll_id: POLL_ID }.data(),
+    };
+    svm.process(ix, &[signer])
+}
+
+fn close_candidate(svm: &mut Svm, creator: &Keypair, name: &str) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::CloseCandidate {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            candidate: candidate_address(0, name),
+            payer: creator.pubkey(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::CloseCandidate {
+            poll_id: POLL_ID,
+            question_index: 0,
+            candidate_name: name.to_string(),
+        }
+        .data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+fn close_receipt(svm: &mut Svm, creator: &Keypair, voter: &Pubkey) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::CloseReceipt {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            receipt: receipt_address(voter),
+            payer: *voter,
+        }
+        .to_account_metas(None),
+        data: voting::instruction::CloseReceipt {
+            poll_id: POLL_ID,
+            voter: *voter,
+        }
+        .data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+fn close_poll(svm: &mut Svm, creator: &Keypair) -> TxResult {
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::ClosePoll {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            summary: summary_address(),
+            system_program: System::id(),
+        }
+        .to_account_metas(None),
+        data: voting::instruction::ClosePoll { poll_id: POLL_ID }.data(),
+    };
+    svm.process(ix, &[creator])
+}
+
+/// A wallet taking part in a conviction poll, with a token account of the stake mint.
+struct Locker {
+    wallet: Keypair,
+    tokens: Pubkey,
+}
+
+struct ConvictionPoll {
+    svm: Svm,
+    creator: Keypair,
+    stake_mint: Pubkey,
+}
+
+impl ConvictionPoll {
+    fn locker(&mut self, balance: u64) -> Locker {
+        let wallet = self.svm.funded_wallet();
+        let tokens = self.svm.create_token_account(&self.stake_mint, &wallet.pubkey(), balance);
+        Locker { wallet, tokens }
+    }
+
+    fn lock(&mut self, locker: &Locker, name: &str, amount: u64) -> TxResult {
+        let voter = locker.wallet.pubkey();
+        let ix = Instruction {
+            program_id: voting::ID,
+            accounts: voting::accounts::LockTokens {
+                signer: voter,
+                poll: poll_address(),
+                candidate: candidate_address(0, name),
+                lock: lock_address(&voter),
+                conviction_voter: conviction_voter_address(&voter),
+                stake_mint: self.stake_mint,
+                vault: vault_address(),
+                voter_token_account: locker.tokens,
+                system_program: System::id(),
+                token_program: anchor_spl::token::ID,
+            }
+            .to_account_metas(None),
+            data: voting::instruction::LockTokens {
+                poll_id: POLL_ID,
+                question_index: 0,
+                candidate_name: name.to_string(),
+                amount,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[&locker.wallet])
+    }
+
+    /// `candidate` is left out once it has been closed.
+    fn unlock(&mut self, locker: &Locker, candidate: Option<&str>) -> TxResult {
+        let voter = locker.wallet.pubkey();
+        let ix = Instruction {
+            program_id: voting::ID,
+            accounts: voting::accounts::UnlockTokens {
+                signer: voter,
+                poll: poll_address(),
+                lock: lock_address(&voter),
+                conviction_voter: conviction_voter_address(&voter),
+                candidate: candidate.map(|name| candidate_address(0, name)),
+                stake_mint: self.stake_mint,
+                vault: vault_address(),
+                voter_token_account: locker.tokens,
+                token_program: anchor_spl::token::ID,
+            }
+            .to_account_metas(None),
+            data: voting::instruction::UnlockTokens {
+                poll_id: POLL_ID,
+                question_index: 0,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[&locker.wallet])
+    }
+
+    fn move_lock(&mut self, locker: &Locker, old_name: &str, new_name: &str) -> TxResult {
+        let voter = locker.wallet.pubkey();
+        let ix = Instruction {
+            program_id: voting::ID,
+            accounts: voting::accounts::MoveLock {
+                signer: voter,
+                poll: poll_address(),
+                lock: lock_address(&voter),
+                old_candidate: candidate_address(0, old_name),
+                new_candidate: candidate_address(0, new_name),
+            }
+            .to_account_metas(None),
+            data: voting::instruction::MoveLock {
+                poll_id: POLL_ID,
+                question_index: 0,
+                new_candidate_name: new_name.to_string(),
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[&locker.wallet])
+    }
+
+    fn candidate(&self, name: &str) -> Candidate {
+        self.svm.account::<Candidate>(&candidate_address(0, name))
+    }
+
+    fn reclaim(&mut self, locker: &Locker) -> TxResult {
+        let voter = locker.wallet.pubkey();
+        let ix = Instruction {
+            program_id: voting::ID,
+            accounts: voting::accounts::ReclaimLock {
+                signer: voter,
+                summary: summary_address(),
+                lock: lock_address(&voter),
+                conviction_voter: conviction_voter_address(&voter),
+                stake_mint: self.stake_mint,
+                vault: vault_address(),
+                voter_token_account: locker.tokens,
+                token_program: anchor_spl::token::ID,
+            }
+            .to_account_metas(None),
+            data: voting::instruction::ReclaimLock {
+                poll_id: POLL_ID,
+                question_index: 0,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[&locker.wallet])
+    }
+}
+
+/// A conviction poll with candidates "yes" and "no", created before it starts.
+fn setup_conviction() -> ConvictionPoll {
+    let mut svm = Svm::new();
+    let creator = svm.funded_wallet();
+    let stake_mint = svm.create_mint(6);
+    initialize_poll(&mut svm, &creator).unwrap();
+
+    let ix = Instruction {
+        program_id: voting::ID,
+        accounts: voting::accounts::EnableConviction {
+            signer: creator.pubkey(),
+            poll: poll_address(),
+            stake_mint,
+            vault: vault_address(),
+            system_program: System::id(),
+            token_program: anchor_spl::token::ID,
+        }
+        .to_account_metas(None),
+        data: voting::instruction::EnableConviction { poll_id: POLL_ID }.data(),
+    };
+    svm.process(ix, &[&creator]).unwrap();
+    initialize_candidate(&mut svm, &creator, 0, "yes").unwrap();
+    initialize_candidate(&mut svm, &creator, 0, "no").unwrap();
+
+    ConvictionPoll { svm, creator, stake_mint }
+}
+
+/// A poll with one candidate, "smooth", created before the poll starts.
+fn setup() -> (Svm, Keypair) {
+    let mut svm = Svm::new();
+    let creator = svm.funded_wallet();
+    initialize_poll(&mut svm, &creator).unwrap();
+    initialize_candidate(&mut svm, &creator, 0, "Smooth").unwrap();
+    (svm, creator)
+}
+
+fn votes_for(svm: &Svm, name: &str) -> u64 {
+    svm.account::<Candidate>(&candidate_address(0, name)).candidate_votes
+}
+
+#[test]
+fn vote_before_start_is_rejected() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+
+    svm.warp_to(POLL_START as i64 - 1);
+    assert_eq!(vote(&mut svm, &voter, "smooth", &[]), Err(voting_error(VotingError::PollNotStarted)));
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+    assert!(svm.get_account(&receipt_address(&voter.pubkey())).is_none());
+}
+
+#[test]
+fn vote_after_end_is_rejected() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+
+    svm.warp_to(POLL_END as i64 + 1);
+    assert_eq!(vote(&mut svm, &voter, "smooth", &[]), Err(voting_error(VotingError::PollEnded)));
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+}
+
+#[test]
+fn poll_window_includes_start_and_end() {
+    let (mut svm, _) = setup();
+    let early = svm.funded_wallet();
+    let late = svm.funded_wallet();
+
+    svm.warp_to(POLL_START as i64);
+    vote(&mut svm, &early, "smooth", &[]).unwrap();
+    svm.warp_to(POLL_END as i64);
+    vote(&mut svm, &late, "smooth", &[]).unwrap();
+
+    assert_eq!(votes_for(&svm, "smooth"), 2);
+    assert_eq!(svm.account::<Poll>(&poll_address()).total_votes, 2);
+}
+
+#[test]
+fn retract_after_end_is_rejected() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+
+    svm.warp_to(POLL_START as i64);
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    svm.warp_to(POLL_END as i64 + 1);
+    assert_eq!(retract_vote(&mut svm, &voter, "smooth", &[]), Err(voting_error(VotingError::PollEnded)));
+    assert_eq!(votes_for(&svm, "smooth"), 1);
+}
+
+#[test]
+fn duplicate_candidate_is_rejected() {
+    let (mut svm, creator) = setup();
+
+    assert_eq!(
+        initialize_candidate(&mut svm, &creator, 0, "Smooth"),
+        Err(voting_error(VotingError::DuplicateCandidate))
+    );
+    // Names are normalized, so case and surrounding whitespace don't make a new candidate.
+    assert_eq!(
+        initialize_candidate(&mut svm, &creator, 0, "  SMOOTH "),
+        Err(voting_error(VotingError::DuplicateCandidate))
+    );
+
+    let poll = svm.account::<Poll>(&poll_address());
+    assert_eq!(poll.candidate_amount, 1);
+    assert_eq!(poll.questions[0].candidate_amount, 1);
+}
+
+#[test]
+fn same_candidate_name_is_allowed_on_another_question() {
+    let (mut svm, creator) = setup();
+    add_question(&mut svm, &creator, "And your favorite jelly?").unwrap();
+
+    initialize_candidate(&mut svm, &creator, 1, "Smooth").unwrap();
+
+    let poll = svm.account::<Poll>(&poll_address());
+    assert_eq!(poll.candidate_amount, 2);
+    assert_eq!(poll.questions[1].candidate_amount, 1);
+}
+
+#[test]
+fn repeated_vote_is_rejected() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    assert_eq!(vote(&mut svm, &voter, "smooth", &[]), Err(voting_error(VotingError::AlreadyVoted)));
+
+    assert_eq!(votes_for(&svm, "smooth"), 1);
+    assert_eq!(svm.account::<Poll>(&poll_address()).total_votes, 1);
+    let receipt = svm.account::<VoteReceipt>(&receipt_address(&voter.pubkey()));
+    assert_eq!(receipt.choices, vec!["smooth".to_string()]);
+}
+
+#[test]
+fn delegator_cannot_vote_after_delegate() {
+    let (mut svm, _) = setup();
+    let delegator = svm.funded_wallet();
+    let delegate = svm.funded_wallet();
+    delegate_vote(&mut svm, &delegator, &delegate.pubkey());
+    svm.warp_to(POLL_START as i64 + 10);
+
+    vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 2);
+
+    assert_eq!(vote(&mut svm, &delegator, "smooth", &[]), Err(voting_error(VotingError::AlreadyVoted)));
+    assert_eq!(votes_for(&svm, "smooth"), 2);
+}
+
+#[test]
+fn pre_funded_delegator_receipt_does_not_block_the_delegate() {
+    let (mut svm, _) = setup();
+    let delegator = svm.funded_wallet();
+    let delegate = svm.funded_wallet();
+    delegate_vote(&mut svm, &delegator, &delegate.pubkey());
+    // Anyone can send lamports to the receipt address before it exists.
+    svm.airdrop(&receipt_address(&delegator.pubkey()), 1_000_000);
+    svm.warp_to(POLL_START as i64 + 10);
+
+    vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 2);
+    let receipt = svm.account::<VoteReceipt>(&receipt_address(&delegator.pubkey()));
+    assert_eq!(receipt.delegate, Some(delegate.pubkey()));
+}
+
+#[test]
+fn retracted_voter_can_vote_again() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    retract_vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+    assert!(svm.get_account(&receipt_address(&voter.pubkey())).is_none());
+
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 1);
+}
+
+#[test]
+fn retracting_a_delegated_vote_frees_the_delegators() {
+    let (mut svm, _) = setup();
+    let delegator = svm.funded_wallet();
+    let delegate = svm.funded_wallet();
+    delegate_vote(&mut svm, &delegator, &delegate.pubkey());
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+
+    retract_vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+    let poll = svm.account::<Poll>(&poll_address());
+    assert_eq!((poll.total_votes, poll.open_receipts), (0, 0));
+    assert!(svm.get_account(&receipt_address(&delegator.pubkey())).is_none());
+
+    vote(&mut svm, &delegator, "smooth", &[]).unwrap();
+    assert_eq!(votes_for(&svm, "smooth"), 1);
+}
+
+#[test]
+fn retracting_a_delegated_vote_needs_every_delegator_receipt() {
+    let (mut svm, _) = setup();
+    let delegator = svm.funded_wallet();
+    let delegate = svm.funded_wallet();
+    let stranger = svm.funded_wallet();
+    delegate_vote(&mut svm, &delegator, &delegate.pubkey());
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+    vote(&mut svm, &stranger, "smooth", &[]).unwrap();
+
+    assert_eq!(
+        retract_vote(&mut svm, &delegate, "smooth", &[]),
+        Err(voting_error(VotingError::IncompleteBallot))
+    );
+    // Someone else's receipt can't stand in for the delegator's.
+    assert_eq!(
+        retract_vote(&mut svm, &delegate, "smooth", &[stranger.pubkey()]),
+        Err(voting_error(VotingError::InvalidReceipt))
+    );
+    assert_eq!(votes_for(&svm, "smooth"), 3);
+    assert!(svm.get_account(&receipt_address(&delegator.pubkey())).is_some());
+}
+
+#[test]
+fn only_the_creator_can_add_questions() {
+    let (mut svm, _) = setup();
+    let stranger = svm.funded_wallet();
+
+    assert_eq!(
+        add_question(&mut svm, &stranger, "And your favorite jelly?"),
+        Err(voting_error(VotingError::NotPollCreator))
+    );
+    assert_eq!(svm.account::<Poll>(&poll_address()).questions.len(), 1);
+}
+
+#[test]
+fn vote_needs_the_voter_signature() {
+    let (mut svm, _) = setup();
+    let voter = svm.funded_wallet();
+    let payer = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+
+    // Casting a vote for a wallet that didn't sign is rejected before the
+    // program does anything.
+    let mut ix = vote_instruction(&voter.pubkey(), "smooth", &[]);
+    ix.accounts[0].is_signer = false;
+    assert_eq!(
+        svm.process(ix, &[&payer]),
+        Err(instruction_error(ErrorCode::AccountNotSigner.into()))
+    );
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+}
+
+#[test]
+fn closed_poll_id_cannot_be_reused() {
+    let (mut svm, creator) = setup();
+    let voter = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+
+    svm.warp_to(POLL_END as i64 + 1);
+    finalize_poll(&mut svm, &creator, &["smooth"]).unwrap();
+    assert_eq!(svm.account::<Poll>(&poll_address()).outcome, PollOutcome::Passed);
+
+    svm.warp_to((POLL_END + CLOSE_GRACE_PERIOD) as i64 + 1);
+    close_candidate(&mut svm, &creator, "smooth").unwrap();
+    // The voter's receipt would be stranded without the poll account.
+    assert_eq!(close_poll(&mut svm, &creator), Err(voting_error(VotingError::ReceiptsRemaining)));
+    close_receipt(&mut svm, &creator, &voter.pubkey()).unwrap();
+    close_poll(&mut svm, &creator).unwrap();
+    assert!(svm.get_account(&poll_address()).is_none());
+
+    assert_eq!(initialize_poll(&mut svm, &creator), Err(voting_error(VotingError::PollIdRetired)));
+}
+
+#[test]
+fn outstanding_lock_does_not_keep_the_poll_open() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(1_000);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 1_000).unwrap();
+
+    poll.svm.warp_to(POLL_END as i64 + 1);
+    let creator = poll.creator.insecure_clone();
+    finalize_poll(&mut poll.svm, &creator, &["yes", "no"]).unwrap();
+    poll.svm.warp_to((POLL_END + CLOSE_GRACE_PERIOD) as i64 + 1);
+    close_candidate(&mut poll.svm, &creator, "yes").unwrap();
+    close_candidate(&mut poll.svm, &creator, "no").unwrap();
+    close_poll(&mut poll.svm, &creator).unwrap();
+
+    // The lock is still there and only comes back through `reclaim_lock`.
+    assert_eq!(poll.svm.token_balance(&vault_address()), 1_000);
+    assert!(poll.unlock(&locker, None).is_err());
+    poll.reclaim(&locker).unwrap();
+    assert_eq!(poll.svm.token_balance(&locker.tokens), 1_000);
+    assert!(poll.svm.get_account(&lock_address(&locker.wallet.pubkey())).is_none());
+    assert!(poll.svm.get_account(&conviction_voter_address(&locker.wallet.pubkey())).is_none());
+}
+
+#[test]
+fn lock_is_unlocked_without_its_closed_candidate() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(1_000);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 1_000).unwrap();
+
+    poll.svm.warp_to(POLL_END as i64 + 1);
+    let creator = poll.creator.insecure_clone();
+    finalize_poll(&mut poll.svm, &creator, &["yes", "no"]).unwrap();
+    poll.svm.warp_to((POLL_END + CLOSE_GRACE_PERIOD) as i64 + 1);
+    close_candidate(&mut poll.svm, &creator, "yes").unwrap();
+
+    poll.unlock(&locker, None).unwrap();
+    assert_eq!(poll.svm.token_balance(&locker.tokens), 1_000);
+    assert!(poll.svm.get_account(&lock_address(&locker.wallet.pubkey())).is_none());
+}
+
+#[test]
+fn candidate_can_only_be_left_out_of_a_finalized_poll() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(1_000);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 1_000).unwrap();
+
+    // Leaving the candidate out would keep the lock's weight on it.
+    assert_eq!(poll.unlock(&locker, None), Err(voting_error(VotingError::PollNotFinalized)));
+    let lock = poll.svm.account::<ConvictionLock>(&lock_address(&locker.wallet.pubkey()));
+    assert_eq!(lock.amount, 1_000);
+}
+
+#[test]
+fn conviction_grows_with_time_and_decides_the_poll() {
+    let mut poll = setup_conviction();
+    let early = poll.locker(100);
+    let late = poll.locker(150);
+
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&early, "yes", 100).unwrap();
+    poll.svm.warp_to(150);
+    poll.lock(&late, "no", 150).unwrap();
+    assert_eq!(poll.svm.token_balance(&vault_address()), 250);
+
+    // 100 tokens for the whole 100 seconds beat 150 tokens for the last 50.
+    assert_eq!(poll.candidate("yes").conviction_at(POLL_END), 10_000);
+    assert_eq!(poll.candidate("no").conviction_at(POLL_END), 7_500);
+
+    poll.svm.warp_to(POLL_END as i64 + 1);
+    let creator = poll.creator.insecure_clone();
+    finalize_poll(&mut poll.svm, &creator, &["yes", "no"]).unwrap();
+    let finalized = poll.svm.account::<Poll>(&poll_address());
+    assert_eq!(finalized.outcome, PollOutcome::Passed);
+    assert_eq!(finalized.total_votes, 2);
+    assert_eq!(finalized.questions[0].winner, Some("yes".to_string()));
+}
+
+#[test]
+fn moving_a_lock_carries_half_its_conviction() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(100);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 100).unwrap();
+
+    poll.svm.warp_to(150);
+    poll.move_lock(&locker, "yes", "no").unwrap();
+
+    assert_eq!(poll.candidate("yes").conviction_at(150), 0);
+    assert_eq!(poll.candidate("yes").conviction_amount, 0);
+    assert_eq!(poll.candidate("no").conviction_at(150), 2_500);
+    assert_eq!(poll.candidate("no").conviction_at(POLL_END), 7_500);
+    let lock = poll.svm.account::<ConvictionLock>(&lock_address(&locker.wallet.pubkey()));
+    assert_eq!(lock.candidate_name, "no");
+    assert_eq!(lock.score, 2_500);
+}
+
+#[test]
+fn unlocking_before_the_end_withdraws_the_lock() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(100);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 100).unwrap();
+
+    poll.svm.warp_to(150);
+    poll.unlock(&locker, Some("yes")).unwrap();
+
+    assert_eq!(poll.svm.token_balance(&locker.tokens), 100);
+    assert_eq!(poll.candidate("yes").conviction_at(POLL_END), 0);
+    assert_eq!(poll.svm.account::<Poll>(&poll_address()).total_votes, 0);
+    assert!(poll.svm.get_account(&conviction_voter_address(&locker.wallet.pubkey())).is_none());
+}
+
+#[test]
+fn unlocking_after_the_end_keeps_the_result() {
+    let mut poll = setup_conviction();
+    let locker = poll.locker(100);
+    poll.svm.warp_to(POLL_START as i64);
+    poll.lock(&locker, "yes", 100).unwrap();
+
+    poll.svm.warp_to(POLL_END as i64 + 1);
+    poll.unlock(&locker, Some("yes")).unwrap();
+
+    assert_eq!(poll.svm.token_balance(&locker.tokens), 100);
+    assert_eq!(poll.candidate("yes").conviction_at(POLL_END), 10_000);
+    assert_eq!(poll.svm.account::<Poll>(&poll_address()).total_votes, 1);
+}
+
+#[test]
+fn changed_vote_moves_its_delegated_weight() {
+    let (mut svm, creator) = setup();
+    initialize_candidate(&mut svm, &creator, 0, "Crunchy").unwrap();
+    let delegator = svm.funded_wallet();
+    let delegate = svm.funded_wallet();
+    delegate_vote(&mut svm, &delegator, &delegate.pubkey());
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &delegate, "smooth", &[delegator.pubkey()]).unwrap();
+
+    change_vote(&mut svm, &delegate, "smooth", "crunchy").unwrap();
+
+    assert_eq!(votes_for(&svm, "smooth"), 0);
+    assert_eq!(votes_for(&svm, "crunchy"), 2);
+    let receipt = svm.account::<VoteReceipt>(&receipt_address(&delegate.pubkey()));
+    assert_eq!(receipt.choices, vec!["crunchy".to_string()]);
+    // Only the delegate decides; the delegator's receipt can't change the vote.
+    assert_eq!(
+        change_vote(&mut svm, &delegator, "crunchy", "smooth"),
+        Err(voting_error(VotingError::DelegatedVote))
+    );
+}
+
+#[test]
+fn change_vote_after_end_is_rejected() {
+    let (mut svm, creator) = setup();
+    initialize_candidate(&mut svm, &creator, 0, "Crunchy").unwrap();
+    let voter = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+
+    svm.warp_to(POLL_END as i64 + 1);
+    assert_eq!(
+        change_vote(&mut svm, &voter, "smooth", "crunchy"),
+        Err(voting_error(VotingError::PollEnded))
+    );
+    assert_eq!(votes_for(&svm, "smooth"), 1);
+}
+
+#[test]
+fn finalize_before_the_end_is_rejected() {
+    let (mut svm, creator) = setup();
+    svm.warp_to(POLL_END as i64);
+    assert_eq!(
+        finalize_poll(&mut svm, &creator, &["smooth"]),
+        Err(voting_error(VotingError::PollNotEnded))
+    );
+}
+
+#[test]
+fn finalize_needs_every_candidate() {
+    let (mut svm, creator) = setup();
+    initialize_candidate(&mut svm, &creator, 0, "Crunchy").unwrap();
+    svm.warp_to(POLL_END as i64 + 1);
+    assert_eq!(
+        finalize_poll(&mut svm, &creator, &["smooth"]),
+        Err(voting_error(VotingError::CandidateCountMismatch))
+    );
+    assert_eq!(
+        finalize_poll(&mut svm, &creator, &["smooth", "smooth"]),
+        Err(voting_error(VotingError::CandidateCountMismatch))
+    );
+}
+
+#[test]
+fn poll_without_votes_has_no_quorum() {
+    let (mut svm, creator) = setup();
+    svm.warp_to(POLL_END as i64 + 1);
+    finalize_poll(&mut svm, &creator, &["smooth"]).unwrap();
+
+    let poll = svm.account::<Poll>(&poll_address());
+    assert_eq!(poll.outcome, PollOutcome::NoQuorum);
+    assert_eq!(poll.questions[0].winner, None);
+    assert_eq!(
+        finalize_poll(&mut svm, &creator, &["smooth"]),
+        Err(voting_error(VotingError::PollAlreadyFinalized))
+    );
+}
+
+#[test]
+fn tied_poll_fails() {
+    let (mut svm, creator) = setup();
+    initialize_candidate(&mut svm, &creator, 0, "Crunchy").unwrap();
+    let first = svm.funded_wallet();
+    let second = svm.funded_wallet();
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &first, "smooth", &[]).unwrap();
+    vote(&mut svm, &second, "crunchy", &[]).unwrap();
+
+    svm.warp_to(POLL_END as i64 + 1);
+    finalize_poll(&mut svm, &creator, &["smooth", "crunchy"]).unwrap();
+    let poll = svm.account::<Poll>(&poll_address());
+    assert_eq!(poll.outcome, PollOutcome::Failed);
+    assert_eq!(poll.questions[0].winner, None);
+}
+
+#[test]
+fn passed_proposal_runs_after_its_timelock() {
+    let (mut svm, creator) = setup();
+    let recipient = svm.funded_wallet().pubkey();
+    let voter = svm.funded_wallet();
+    svm.airdrop(&treasury_address(), 1_000_000_000);
+    create_proposal(&mut svm, &creator, &recipient, 5_000, 50).unwrap();
+
+    svm.warp_to(POLL_START as i64 + 10);
+    vote(&mut svm, &voter, "smooth", &[]).unwrap();
+    svm.warp_to(POLL_END as i64 + 1);
+    finalize_poll(&mut svm, &creator, &["smooth"]).unwrap();
+
+    assert_eq!(
+        execute_proposal(&mut svm, &voter, &recipient),
+        Err(voting_error(VotingError::TimelockActive))
+    );
+    let before = svm.get_account(&recipient).unwrap().lamports;
+    svm.warp_to(POLL_END as i64 + 51);
+    execute_proposal(&mut svm, &voter, &recipient).unwrap();
+    assert_eq!(svm.get_account(&recipient).unwrap().lamports, before + 5_000);
+
+    assert_eq!(
+        execute_proposal(&mut svm, &voter, &recipient),
+        Err(voting_error(VotingError::ProposalAlreadyExecuted))
+    );
+}
+
+#[test]
+fn proposal_of_a_poll_that_did_not_pass_never_runs() {
+    let (mut svm, creator) = setup();
+    let recipient = svm.funded_wallet().pubkey();
+    svm.airdrop(&treasury_address(), 1_000_000_000);
+    create_proposal(&mut svm, &creator, &recipient, 5_000, 0).unwrap();
+
+    svm.warp_to(POLL_END as i64 + 1);
+    finalize_poll(&mut svm, &creator, &["smooth"]).unwrap();
+    assert_eq!(
+        execute_proposal(&mut svm, &creator, &recipient),
+        Err(voting_error(VotingError::PollNotPassed))
+    );
+}
+
+#[test]
+fn proposal_after_the_start_is_rejected() {
+    let (mut svm, creator) = setup();
+    let recipient = svm.funded_wallet().pubkey();
+    svm.warp_to(POLL_START as i64);
+    assert_eq!(
+        create_proposal(&mut svm, &creator, &recipient, 5_000, 0),
+        Err(voting_error(VotingError::PollAlreadyStarted))
+    );
+    assert!(svm.get_account(&proposal_address()).is_none());
+}
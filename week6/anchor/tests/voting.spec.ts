@@ -34,6 +34,8 @@ describe('Voting', () => {
       "What is your favorite type of peanut butter?",
       new anchor.BN(0),
       new anchor.BN(1821246480),
+      new anchor.BN(1),
+      5000,
     ).rpc();
 
     const [pollAddress] = PublicKey.findProgramAddressSync(
@@ -54,14 +56,16 @@ describe('Voting', () => {
     await votingProgram.methods.initializeCandidate(
       "Smooth",
       new anchor.BN(1),
+      0,
     ).rpc();
     await votingProgram.methods.initializeCandidate(
       "Crunchy",
       new anchor.BN(1),
+      0,
     ).rpc();
 
     const [crunchyAddress] = PublicKey.findProgramAddressSync(
-      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from("Crunchy")],
+      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from([0]), Buffer.from("crunchy")],
       votingAddress,
     );
     const crunchyCandidate = await votingProgram.account.candidate.fetch(crunchyAddress);
@@ -69,7 +73,7 @@ describe('Voting', () => {
     expect(crunchyCandidate.candidateVotes.toNumber()).toEqual(0);
 
     const [smoothAddress] = PublicKey.findProgramAddressSync(
-      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from("Smooth")],
+      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from([0]), Buffer.from("smooth")],
       votingAddress,
     );
     const smoothCandidate = await votingProgram.account.candidate.fetch(smoothAddress);
@@ -78,16 +82,19 @@ describe('Voting', () => {
   });
 
   it("vote", async() => {
+    const [smoothAddress] = PublicKey.findProgramAddressSync(
+      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from([0]), Buffer.from("smooth")],
+      votingAddress,
+    );
+
     await votingProgram.methods
       .vote(
-        "Smooth",
+        ["Smooth"],
         new anchor.BN(1)
-      ).rpc()
+      )
+      .remainingAccounts([{ pubkey: smoothAddress, isSigner: false, isWritable: true }])
+      .rpc()
 
-    const [smoothAddress] = PublicKey.findProgramAddressSync(
-      [new anchor.BN(1).toArrayLike(Buffer, 'le', 8), Buffer.from("Smooth")],
-      votingAddress,
-    );
     const smoothCandidate = await votingProgram.account.candidate.fetch(smoothAddress);
     console.log(smoothCandidate);
     expect(smoothCandidate.candidateVotes.toNumber()).toEqual(1);
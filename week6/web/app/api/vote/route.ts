@@ -12,6 +12,12 @@ const IDL = require("@/../anchor/target/idl/voting.json");
 
 export const OPTIONS = GET;
 
+// Mirrors `candidate_seed` in the program: names are trimmed and lowercased,
+// then clamped to the 32-byte seed limit.
+function candidateSeed(name: string): Buffer {
+  return Buffer.from(name.trim().toLowerCase()).subarray(0, 32);
+}
+
 export async function GET(request: Request) {
   const actionMetadata: ActionGetResponse = {
     icon: "https://zestfulkitchen.com/wp-content/uploads/2021/09/Peanut-butter_hero_for-web-2.jpg",
@@ -39,7 +45,7 @@ export async function GET(request: Request) {
 
 export async function POST(request: Request) {
   const url = new URL(request.url, "http://localhost:3001");
-  const candidate = url.searchParams.get("candidate");
+  const candidate = url.searchParams.get("candidate")?.trim();
 
   if (candidate !== "Crunchy" && candidate !== "Smooth") {
     return new Response("Invalid candidate", {
@@ -63,11 +69,18 @@ export async function POST(request: Request) {
     });
   }
 
+  const pollId = new BN(1);
+  const [candidateAddress] = PublicKey.findProgramAddressSync(
+    [pollId.toArrayLike(Buffer, "le", 8), Buffer.from([0]), candidateSeed(candidate)],
+    program.programId,
+  );
+
   const instruction = await program.methods
-    .vote(candidate, new BN(1))
+    .vote([candidate], pollId)
     .accounts({
       signer: voter,
     })
+    .remainingAccounts([{ pubkey: candidateAddress, isSigner: false, isWritable: true }])
     .instruction();
 
   const blockhash = await connection.getLatestBlockhash();